@@ -18,7 +18,7 @@ fn main() {
     let mut p = Problem::new();
     for (i, r) in matrix.chunks(NUM_COLUMNS).enumerate() {
         let column_indices: Vec<usize> = r.iter().enumerate().filter_map(|(i, c)| if *c == 1 { Some(i) } else { None }).collect();
-        p.add_action(i, &column_indices);
+        p.add_action(i, &column_indices).unwrap();
     }
 
     let mut solver = Solver::new(p);
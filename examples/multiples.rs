@@ -5,14 +5,14 @@ use exact_cover::{Solver};
 
 fn main() {
     let mut p = Problem::new();
-    p.add_action("01", &[0, 1]);
-    p.add_action("03", &[0, 3]);
-    p.add_action("12", &[1, 2]);
-    p.add_action("23", &[2, 3]);
-    p.add_action("0", &[0]);
-    p.add_action("1", &[1]);
-    p.add_action("2", &[2]);
-    p.add_action("3", &[3]);
+    p.add_action("01", &[0, 1]).unwrap();
+    p.add_action("03", &[0, 3]).unwrap();
+    p.add_action("12", &[1, 2]).unwrap();
+    p.add_action("23", &[2, 3]).unwrap();
+    p.add_action("0", &[0]).unwrap();
+    p.add_action("1", &[1]).unwrap();
+    p.add_action("2", &[2]).unwrap();
+    p.add_action("3", &[3]).unwrap();
 
 
     let solver = Solver::new(p);
@@ -0,0 +1,38 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem, Solver};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Constraint {
+    Row(usize),
+    Col(usize),
+    Diag(usize),
+    AntiDiag(usize)
+}
+
+fn main() {
+    const N: usize = 8;
+
+    // Every row and column must contain exactly one queen, but a
+    // diagonal may contain at most one queen, so the diagonals are
+    // added as secondary constraints.
+    let mut p = Problem::new();
+    for i in 0..(2*N - 1) {
+        p.add_secondary_constraint(&Constraint::Diag(i));
+        p.add_secondary_constraint(&Constraint::AntiDiag(i));
+    }
+
+    for r in 0..N {
+        for c in 0..N {
+            p.add_action((r, c), &[Constraint::Row(r), Constraint::Col(c),
+                                   Constraint::Diag(r + c), Constraint::AntiDiag(N - 1 + r - c)]).unwrap();
+        }
+    }
+
+    let solver = Solver::new(p);
+    let sol = solver.first_solution().unwrap();
+    for r in 0..N {
+        let line: String = (0..N).map(|c| if sol.contains(&(r, c)) { 'Q' } else { '.' }).collect();
+        println!("{}", line);
+    }
+}
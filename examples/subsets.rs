@@ -14,12 +14,12 @@ fn main() {
     // that the action satisfies. In this example, each constraint is
     // simply a number, which implicitly means that the subset
     // includes that number.
-    p.add_action("A", &[1, 4, 7]);
-    p.add_action("B", &[1, 4]);
-    p.add_action("C", &[4, 5, 7]);
-    p.add_action("D", &[3, 5, 6]);
-    p.add_action("E", &[2, 3, 6, 7]);
-    p.add_action("F", &[2, 7]);
+    p.add_action("A", &[1, 4, 7]).unwrap();
+    p.add_action("B", &[1, 4]).unwrap();
+    p.add_action("C", &[4, 5, 7]).unwrap();
+    p.add_action("D", &[3, 5, 6]).unwrap();
+    p.add_action("E", &[2, 3, 6, 7]).unwrap();
+    p.add_action("F", &[2, 7]).unwrap();
 
     // Once we add the actions, create a solver to solve the problem.
     let solver = Solver::new(p);
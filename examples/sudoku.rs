@@ -25,6 +25,7 @@ fn read_sudoku() -> (String, Option<SudokuSolver>) {
 }
 
 /// Read a sudoku from the command line and solve.
+#[allow(clippy::println_empty_string)]
 fn main() {
 
     loop {
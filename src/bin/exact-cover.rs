@@ -0,0 +1,202 @@
+//! Solve exact cover problems read from a file or standard input.
+
+extern crate exact_cover;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use exact_cover::Solver;
+use exact_cover::formats::{dlx, simple};
+
+const USAGE: &str = "\
+usage: exact-cover [OPTIONS] COMMAND [FILE]
+
+Read an exact cover problem from FILE (or standard input, if FILE is
+missing or '-') and solve it. Solutions are printed one action per
+line, each followed by an empty line.
+
+Commands:
+  first              print the first solution
+  all                print every solution
+  count              print the number of solutions
+  random             print a solution picked at random
+
+Options:
+  --format FORMAT    'dlx' for Knuth's DLX format, or 'simple' for
+                     lines of 'action: constraint constraint ...'
+                     (guessed from the first line by default)
+  --require ACTION   only find solutions including ACTION; in the DLX
+                     format, an option is named by its items as
+                     written, or by its number (from 0)
+  --seed N           seed for 'random' (default 0)
+  --limit N          stop 'all' and 'count' after N solutions
+  -h, --help         print this message
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Dlx,
+    Simple
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    First,
+    All,
+    Count,
+    Random
+}
+
+struct Options {
+    command: Command,
+    file: Option<String>,
+    format: Option<Format>,
+    require: Vec<String>,
+    seed: u64,
+    limit: Option<usize>
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut command = None;
+    let mut file = None;
+    let mut format = None;
+    let mut require = Vec::new();
+    let mut seed = 0;
+    let mut limit = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().cloned().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--format" => format = Some(match value(arg)?.as_str() {
+                "dlx" => Format::Dlx,
+                "simple" => Format::Simple,
+                f => return Err(format!("unknown format {:?}", f))
+            }),
+            "--require" => require.push(value(arg)?),
+            "--seed" => seed = value(arg)?.parse().map_err(|_| "--seed needs a number".to_string())?,
+            "--limit" => limit = Some(value(arg)?.parse().map_err(|_| "--limit needs a number".to_string())?),
+            a if a.starts_with('-') && a != "-" => return Err(format!("unknown option {}", a)),
+            a if command.is_none() => command = Some(match a {
+                "first" => Command::First,
+                "all" => Command::All,
+                "count" => Command::Count,
+                "random" => Command::Random,
+                c => return Err(format!("unknown command {:?}", c))
+            }),
+            a if file.is_none() => file = Some(a.to_string()),
+            a => return Err(format!("unexpected argument {:?}", a))
+        }
+    }
+
+    let command = command.ok_or_else(|| "missing command".to_string())?;
+    Ok(Options { command, file, format, require, seed, limit })
+}
+
+/// Tell the formats apart by the first line that isn't blank or a
+/// comment: a DLX item list has no colons, except in bounds, which
+/// come with a '|'.
+fn guess_format(input: &str) -> Format {
+    let first = input.lines()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('|'))
+        .and_then(|l| l.split_whitespace().next());
+    match first {
+        Some(token) if token.contains(':') && !token.contains('|') => Format::Simple,
+        _ => Format::Dlx
+    }
+}
+
+fn read_input(file: &Option<String>) -> Result<String, String> {
+    match file.as_ref().map(|f| f.as_str()) {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map_err(|e| format!("standard input: {}", e))?;
+            Ok(input)
+        }
+        Some(f) => fs::read_to_string(f).map_err(|e| format!("{}: {}", f, e))
+    }
+}
+
+fn print_solution<W: Write>(out: &mut W, names: &[String], solution: &[usize]) -> io::Result<()> {
+    for &a in solution {
+        writeln!(out, "{}", names[a])?;
+    }
+    writeln!(out)
+}
+
+/// Solve the problem, returning false if a solution was asked for but
+/// none exists.
+fn run(options: &Options) -> Result<bool, String> {
+    let input = read_input(&options.file)?;
+    let format = options.format.unwrap_or_else(|| guess_format(&input));
+    let (problem, names) = match format {
+        Format::Dlx => dlx::parse_named(&input),
+        Format::Simple => simple::parse(&input)
+    }.map_err(|e| e.to_string())?;
+
+    let mut solver = Solver::new(problem);
+    for name in &options.require {
+        let action = names.iter().position(|n| n == name)
+            .or_else(|| if format == Format::Dlx { name.parse().ok() } else { None })
+            .ok_or_else(|| format!("unknown action {:?}", name))?;
+        solver.require_action(action).map_err(|e| e.to_string())?;
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let result = match options.command {
+        Command::First | Command::Random => {
+            let solution = if options.command == Command::First {
+                solver.first_solution()
+            } else {
+                solver.random_solution(options.seed)
+            };
+            match solution {
+                Some(s) => print_solution(&mut out, &names, &s).map(|_| true),
+                None => {
+                    eprintln!("no solution");
+                    Ok(false)
+                }
+            }
+        }
+        Command::All => {
+            let limit = options.limit.unwrap_or(usize::MAX);
+            solver.into_iter().take(limit)
+                .try_for_each(|s| print_solution(&mut out, &names, &s))
+                .map(|_| true)
+        }
+        Command::Count => writeln!(out, "{}", solver.count_solutions(options.limit)).map(|_| true)
+    };
+    match result {
+        Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(true),
+        r => r.map_err(|e| e.to_string())
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        print!("{}", USAGE);
+        return;
+    }
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("exact-cover: {}", e);
+            eprintln!("Try 'exact-cover --help' for more information.");
+            process::exit(2);
+        }
+    };
+    match run(&options) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("exact-cover: {}", e);
+            process::exit(2);
+        }
+    }
+}
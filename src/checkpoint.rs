@@ -0,0 +1,172 @@
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+/// The first word of every checkpoint, changed whenever the layout
+/// changes.
+const VERSION: u64 = 2;
+
+/// A `Checkpoint` records the position of a `SolutionIterator`, so the
+/// iteration can be picked up later, possibly in another process (see
+/// `SolutionIterator::checkpoint()` and `SolutionIterator::resume()`).
+///
+/// A checkpoint is a plain sequence of numbers: it can be saved via
+/// `words()` or `to_string()`, and read back via `from_words()` or
+/// `parse()`, or with serde, when the `serde` feature is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Checkpoint {
+    words: Vec<u64>
+}
+
+impl Checkpoint {
+    pub fn from_words(words: Vec<u64>) -> Checkpoint {
+        Checkpoint { words }
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, w) in self.words.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", w)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Checkpoint, ParseIntError> {
+        let words = s.split_whitespace().map(|w| w.parse()).collect::<Result<_, _>>()?;
+        Ok(Checkpoint { words })
+    }
+}
+
+/// Builds a checkpoint.
+pub(crate) struct Writer {
+    words: Vec<u64>
+}
+
+impl Writer {
+    pub fn new() -> Writer {
+        Writer { words: vec![VERSION] }
+    }
+
+    pub fn finish(self) -> Checkpoint {
+        Checkpoint { words: self.words }
+    }
+
+    pub fn usize(&mut self, x: usize) {
+        self.words.push(x as u64);
+    }
+
+    pub fn bool(&mut self, b: bool) {
+        self.words.push(b as u64);
+    }
+
+    pub fn option(&mut self, x: Option<u64>) {
+        self.bool(x.is_some());
+        if let Some(x) = x {
+            self.words.push(x);
+        }
+    }
+
+    pub fn list<I: IntoIterator<Item = usize>>(&mut self, xs: I) {
+        let start = self.words.len();
+        self.words.push(0);
+        for x in xs {
+            self.words.push(x as u64);
+        }
+        self.words[start] = (self.words.len() - start - 1) as u64;
+    }
+
+    pub fn option_list<I: IntoIterator<Item = usize>>(&mut self, xs: Option<I>) {
+        self.bool(xs.is_some());
+        if let Some(xs) = xs {
+            self.list(xs);
+        }
+    }
+}
+
+/// Reads a checkpoint back, returning None as soon as anything is
+/// out of place.
+pub(crate) struct Reader<'a> {
+    words: &'a [u64]
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(checkpoint: &'a Checkpoint) -> Option<Reader<'a>> {
+        let mut r = Reader { words: &checkpoint.words };
+        if r.word()? != VERSION {
+            return None;
+        }
+        Some(r)
+    }
+
+    /// Return true if the whole checkpoint has been read.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn word(&mut self) -> Option<u64> {
+        let (w, rest) = self.words.split_first()?;
+        self.words = rest;
+        Some(*w)
+    }
+
+    /// Read a number smaller than `bound`.
+    pub fn index(&mut self, bound: usize) -> Option<usize> {
+        let w = self.word()?;
+        if w < bound as u64 { Some(w as usize) } else { None }
+    }
+
+    pub fn usize(&mut self) -> Option<usize> {
+        self.index(usize::MAX)
+    }
+
+    pub fn bool(&mut self) -> Option<bool> {
+        self.index(2).map(|b| b == 1)
+    }
+
+    pub fn option(&mut self) -> Option<Option<u64>> {
+        if self.bool()? {
+            self.word().map(Some)
+        } else {
+            Some(None)
+        }
+    }
+
+    /// Read an optional number smaller than `bound`.
+    pub fn option_index(&mut self, bound: usize) -> Option<Option<usize>> {
+        if self.bool()? {
+            self.index(bound).map(Some)
+        } else {
+            Some(None)
+        }
+    }
+
+    /// Read a list of numbers smaller than `bound`.
+    pub fn list(&mut self, bound: usize) -> Option<Vec<usize>> {
+        let n = self.index(self.words.len() + 1)?;
+        (0..n).map(|_| self.index(bound)).collect()
+    }
+
+    pub fn option_list(&mut self, bound: usize) -> Option<Option<Vec<usize>>> {
+        if self.bool()? {
+            self.list(bound).map(Some)
+        } else {
+            Some(None)
+        }
+    }
+}
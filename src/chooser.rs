@@ -0,0 +1,191 @@
+//! Strategies for choosing the constraint to branch on at each node
+//! of the search.
+//!
+//! `Solver` uses `Mrv` unless told otherwise (see
+//! `Solver::with_chooser()`); any type implementing `ColumnChooser`
+//! can take its place.
+
+use node::{Matrix, ColumnIndex};
+use iter::iter_columns;
+use rng::Rng;
+
+/// A read-only view of the constraints that still have to be
+/// satisfied at a node of the search.
+pub struct Columns<'a, C: 'a> {
+    matrix: &'a Matrix,
+    names: &'a [C]
+}
+
+impl<'a, C> Clone for Columns<'a, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, C> Copy for Columns<'a, C> {}
+
+impl<'a, C> Columns<'a, C> {
+    /// Iterate over the constraints that may still be branched on:
+    /// primary constraints that could take more actions, in the order
+    /// they were added to the problem.
+    pub fn iter(&self) -> impl Iterator<Item = Column<'a, C>> + 'a {
+        let (matrix, names) = (self.matrix, self.names);
+        iter_columns(matrix).map(move |index| Column { matrix, names, index })
+    }
+}
+
+/// A primary constraint that could take more actions.
+pub struct Column<'a, C: 'a> {
+    matrix: &'a Matrix,
+    names: &'a [C],
+    index: ColumnIndex
+}
+
+impl<'a, C> Clone for Column<'a, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, C> Copy for Column<'a, C> {}
+
+impl<'a, C> Column<'a, C> {
+    pub fn constraint(&self) -> &'a C {
+        &self.names[self.index]
+    }
+
+    /// Return the number of actions that can still satisfy the
+    /// constraint.
+    pub fn len(&self) -> usize {
+        self.matrix.len(self.index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of additional actions needed to satisfy the
+    /// constraint (1 for an ordinary constraint).
+    pub fn need(&self) -> usize {
+        self.matrix.bounds(self.index).need()
+    }
+
+    /// Return the number of branches the search would take on the
+    /// constraint, or 0 if it can no longer be satisfied. For an
+    /// ordinary constraint, this is `len()`.
+    pub fn degree(&self) -> usize {
+        self.matrix.degree(self.index)
+    }
+}
+
+/// A `ColumnChooser` picks the constraint to branch on at each node
+/// of the search.
+///
+/// Any choice leads to the same set of solutions, but the size of the
+/// search tree (and the order of the solutions) depends heavily on
+/// it. Choosing a constraint of degree 0 as soon as one exists prunes
+/// dead ends early.
+pub trait ColumnChooser<C> {
+    /// Choose one of `columns`, which is never empty.
+    fn choose<'a>(&mut self, columns: Columns<'a, C>) -> Column<'a, C>;
+}
+
+/// Choose the constraint with the fewest remaining options (minimum
+/// remaining values), taking the first one on ties. This is the
+/// default, and usually the best general-purpose strategy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mrv;
+
+impl<C> ColumnChooser<C> for Mrv {
+    fn choose<'a>(&mut self, columns: Columns<'a, C>) -> Column<'a, C> {
+        columns.iter().min_by_key(|c| c.degree()).unwrap()
+    }
+}
+
+/// Choose the first constraint that still has to be satisfied, in the
+/// order constraints were added.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FirstColumn;
+
+impl<C> ColumnChooser<C> for FirstColumn {
+    fn choose<'a>(&mut self, columns: Columns<'a, C>) -> Column<'a, C> {
+        columns.iter().next().unwrap()
+    }
+}
+
+/// Like `Mrv`, but break ties uniformly at random. The same seed
+/// always makes the same choices.
+#[derive(Debug, Clone)]
+pub struct MrvRandom {
+    rng: Rng
+}
+
+impl MrvRandom {
+    pub fn new(seed: u64) -> MrvRandom {
+        MrvRandom { rng: Rng::new(seed) }
+    }
+}
+
+impl<C> ColumnChooser<C> for MrvRandom {
+    fn choose<'a>(&mut self, columns: Columns<'a, C>) -> Column<'a, C> {
+        let mut best: Option<Column<'a, C>> = None;
+        let mut ties = 0;
+        for c in columns.iter() {
+            match best.map(|b| c.degree().cmp(&b.degree())) {
+                Some(::std::cmp::Ordering::Greater) => continue,
+                Some(::std::cmp::Ordering::Equal) => ties += 1,
+                _ => ties = 1
+            }
+            // Keep each of the tied columns with equal probability.
+            if ties == 1 || self.rng.below(ties) == 0 {
+                best = Some(c);
+            }
+        }
+        best.unwrap()
+    }
+}
+
+/// Choose constraints by a user-supplied rank first, and by `Mrv`
+/// among constraints of the same rank; lower ranks are chosen first.
+/// Constraints of degree 0 or 1 are always chosen first, whatever
+/// their rank, since they cost no branching.
+///
+/// ```
+/// use exact_cover::chooser::MrvPriority;
+/// use exact_cover::instances::sudoku::SudokuConstraint;
+///
+/// // Fill in cells before looking at rows, columns and boxes.
+/// let chooser = MrvPriority::new(|c: &SudokuConstraint| match *c {
+///     SudokuConstraint::Existence(_, _) => 0,
+///     SudokuConstraint::Uniqueness(_, _) => 1
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct MrvPriority<F> {
+    rank: F
+}
+
+impl<F> MrvPriority<F> {
+    pub fn new(rank: F) -> MrvPriority<F> {
+        MrvPriority { rank }
+    }
+}
+
+impl<C, F: FnMut(&C) -> usize> ColumnChooser<C> for MrvPriority<F> {
+    fn choose<'a>(&mut self, columns: Columns<'a, C>) -> Column<'a, C> {
+        let rank = &mut self.rank;
+        columns.iter()
+            .min_by_key(|c| {
+                let d = c.degree();
+                if d <= 1 { (0, 0, d) } else { (1, rank(c.constraint()), d) }
+            })
+            .unwrap()
+    }
+}
+
+/// Return the column chosen by `chooser` among the columns of `m`
+/// that may still be branched on, if there are any.
+pub(crate) fn choose_column<C, H: ColumnChooser<C>>(m: &Matrix, names: &[C], chooser: &mut H) -> Option<ColumnIndex> {
+    iter_columns(m).next()?;
+    Some(chooser.choose(Columns { matrix: m, names }).index)
+}
@@ -0,0 +1,45 @@
+use node::{Matrix, ColumnIndex, NONE};
+use iter::{iter_col, iter_row, iter_columns};
+
+/// Return the representative of the set containing `c`, halving the
+/// path on the way.
+fn find(parent: &mut [ColumnIndex], mut c: ColumnIndex) -> ColumnIndex {
+    while parent[c] != c {
+        parent[c] = parent[parent[c]];
+        c = parent[c];
+    }
+    c
+}
+
+/// Group the columns that may still be branched on into independent
+/// components. Two columns are in the same component iff they are
+/// connected by a chain of live rows, through any of the columns
+/// those rows occupy (including secondary ones); the solutions of the
+/// matrix are then the products of the solutions of its components.
+///
+/// Components are returned in the order of their first column in the
+/// header list.
+pub fn components(m: &Matrix) -> Vec<Vec<ColumnIndex>> {
+    let mut parent: Vec<ColumnIndex> = (0..m.num_columns()).collect();
+    for c in iter_columns(m) {
+        for node in iter_col(m, c) {
+            for n in iter_row(m, node) {
+                let a = find(&mut parent, c);
+                let b = find(&mut parent, m.column(n));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut index = vec![NONE; m.num_columns()];
+    let mut comps: Vec<Vec<ColumnIndex>> = Vec::new();
+    for c in iter_columns(m) {
+        let r = find(&mut parent, c);
+        if index[r] == NONE {
+            index[r] = comps.len();
+            comps.push(Vec::new());
+        }
+        comps[index[r]].push(c);
+    }
+    comps
+}
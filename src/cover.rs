@@ -1,96 +1,200 @@
-use node::{get_header, WeakNode, OwnedNode};
-use iter::{iter_row, iter_col};
-
-/// Structure to temporarily cover a column. The column is
-/// automatically covered when the instance is dropped.
-pub struct TempCoverColumn<'a> {
-    node: &'a OwnedNode,
+use node::{Matrix, NodeIndex, ColumnIndex, Color};
+
+/// Remove every row still in the column from all of the other
+/// columns it occupies.
+pub fn hide_rows(m: &mut Matrix, c: ColumnIndex) {
+    let h = m.header(c);
+    let mut r = m.down(h);
+    while r != h {
+        // For every node in the row (except the one from this
+        // constraint), remove the node from its column, which
+        // decrements the corresponding count
+        let mut n = m.right(r);
+        while n != r {
+            m.remove_from_column(n);
+            n = m.right(n);
+        }
+        r = m.down(r);
+    }
 }
 
-impl<'a> TempCoverColumn<'a> {
-    pub fn new(node: &'a OwnedNode) -> TempCoverColumn<'a> {
-        cover_column(node);
-        TempCoverColumn { node: node }
+/// Undo `hide_rows()`.
+pub fn unhide_rows(m: &mut Matrix, c: ColumnIndex) {
+    let h = m.header(c);
+    let mut r = m.up(h);
+    while r != h {
+        // For every node in the row (except the one from this
+        // constraint), reinsert node into its column, which
+        // increments that column's count.
+        let mut n = m.left(r);
+        while n != r {
+            m.reinsert_into_column(n);
+            n = m.left(n);
+        }
+        r = m.up(r);
     }
 }
 
-impl<'a> Drop for TempCoverColumn<'a> {
-    fn drop(&mut self) {
-        uncover_column(&self.node);
+/// Record that one more action satisfies the column. Once the upper
+/// bound of the column is met, the remaining rows of the column are
+/// hidden, and a primary column is removed from the header list, so
+/// it's no longer branched on.
+pub fn cover_column(m: &mut Matrix, c: ColumnIndex) {
+    m.inc_used(c);
+    let b = m.bounds(c);
+
+    if b.is_full() {
+        if b.is_primary() {
+            let h = m.header(c);
+            m.remove_from_row(h);
+        }
+        hide_rows(m, c);
     }
 }
 
-pub fn try_cover_column(col: &OwnedNode) -> TempCoverColumn {
-    TempCoverColumn::new(col)
-}
+/// Undo `cover_column()`.
+pub fn uncover_column(m: &mut Matrix, c: ColumnIndex) {
+    let b = m.bounds(c);
 
-pub fn cover_column(col: &OwnedNode) {
-    col.borrow_mut().remove_from_row();
+    if b.is_full() {
+        unhide_rows(m, c);
+        if b.is_primary() {
+            let h = m.header(c);
+            m.reinsert_into_row(h);
+        }
+    }
+    m.dec_used(c);
+}
 
-    for r in iter_col(col) {
-        // For every node in the row (except the one from this
-        // constraint), remove the node from its column and
-        // decrement the corresponding count
-        for n in iter_row(&r) {
-            let sn = n.upgrade().unwrap();
-            //let ci = sn.borrow().column.unwrap();
-
-            sn.borrow_mut().remove_from_column();
-            let header = sn.borrow().get_header().upgrade().unwrap();
-            header.borrow_mut().dec_count();
+/// Hide every row in the column whose node doesn't have the given
+/// color. Rows of the same color are left alone, so several of them
+/// can share the column.
+fn purify(m: &mut Matrix, c: ColumnIndex, color: Color) {
+    let h = m.header(c);
+    let mut r = m.down(h);
+    while r != h {
+        if m.color(r) != Some(color) {
+            let mut n = m.right(r);
+            while n != r {
+                m.remove_from_column(n);
+                n = m.right(n);
+            }
         }
+        r = m.down(r);
     }
 }
 
-pub fn uncover_column(col: &OwnedNode) {
-    for r in iter_col(col).rev() {
-        // For every node in the row (except the one from this
-        // constraint), reinsert node into its column and
-        // increment that column's count.
-        for n in iter_row(&r).rev() {
-            let sn = n.upgrade().unwrap();
-            //let ci = sn.borrow().column.unwrap();
-
-            sn.borrow_mut().reinsert_into_column();
-            let header = sn.borrow().get_header().upgrade().unwrap();
-            header.borrow_mut().inc_count();
+/// Undo `purify()`.
+fn unpurify(m: &mut Matrix, c: ColumnIndex, color: Color) {
+    let h = m.header(c);
+    let mut r = m.up(h);
+    while r != h {
+        if m.color(r) != Some(color) {
+            let mut n = m.left(r);
+            while n != r {
+                m.reinsert_into_column(n);
+                n = m.left(n);
+            }
         }
+        r = m.up(r);
     }
+}
 
-    col.borrow_mut().reinsert_into_row();
+/// Record that one more action satisfies the column with the given
+/// color. The first such action purifies the column.
+pub fn cover_color(m: &mut Matrix, c: ColumnIndex, color: Color) {
+    match m.purity(c) {
+        None => {
+            purify(m, c, color);
+            m.set_purity(c, Some((color, 1)));
+        },
+        Some((p, n)) => m.set_purity(c, Some((p, n + 1)))
+    }
+}
 
+/// Undo `cover_color()`.
+pub fn uncover_color(m: &mut Matrix, c: ColumnIndex) {
+    let (p, n) = m.purity(c).unwrap();
+    if n == 1 {
+        m.set_purity(c, None);
+        unpurify(m, c, p);
+    } else {
+        m.set_purity(c, Some((p, n - 1)));
+    }
 }
 
-pub fn cover_row(node: &WeakNode) {
-    for c in iter_row(node) {
-        cover_column(&get_header(&c).upgrade().unwrap())
+/// Cover the column of `node`, respecting the node's color.
+fn cover_node(m: &mut Matrix, node: NodeIndex) {
+    let c = m.column(node);
+    match m.color(node) {
+        Some(color) => cover_color(m, c, color),
+        None => cover_column(m, c)
     }
 }
 
-pub fn uncover_row(node: &WeakNode) {
-    for c in iter_row(node).rev() {
-        uncover_column(&get_header(&c).upgrade().unwrap())
+/// Undo `cover_node()`.
+fn uncover_node(m: &mut Matrix, node: NodeIndex) {
+    let c = m.column(node);
+    match m.color(node) {
+        Some(_) => uncover_color(m, c),
+        None => uncover_column(m, c)
     }
 }
 
-pub struct TempCoverRow<'a> {
-    node: &'a WeakNode
+/// Cover every column in the row occupied by `node`, except the
+/// column of `node` itself.
+pub fn cover_row(m: &mut Matrix, node: NodeIndex) {
+    let mut n = m.right(node);
+    while n != node {
+        cover_node(m, n);
+        n = m.right(n);
+    }
 }
 
-impl<'a> TempCoverRow<'a> {
-    pub fn new(node: &'a WeakNode) -> TempCoverRow<'a> {
-        cover_row(node);
+pub fn uncover_row(m: &mut Matrix, node: NodeIndex) {
+    let mut n = m.left(node);
+    while n != node {
+        uncover_node(m, n);
+        n = m.left(n);
+    }
+}
 
-        TempCoverRow {node: node}
+/// Remove every node of the row occupied by `node` (including `node`
+/// itself) from its column.
+pub fn hide_row(m: &mut Matrix, node: NodeIndex) {
+    let mut n = node;
+    loop {
+        m.remove_from_column(n);
+        n = m.right(n);
+        if n == node {
+            break;
+        }
     }
 }
 
-impl<'a> Drop for TempCoverRow<'a> {
-    fn drop(&mut self) {
-        uncover_row(self.node);
+/// Undo `hide_row()`.
+pub fn unhide_row(m: &mut Matrix, node: NodeIndex) {
+    let mut n = node;
+    loop {
+        n = m.left(n);
+        m.reinsert_into_column(n);
+        if n == node {
+            break;
+        }
     }
 }
 
-pub fn try_cover_row(row: &WeakNode) -> TempCoverRow {
-    TempCoverRow::new(row)
+/// Make the row occupied by `node` part of the solution: the row is
+/// removed from the matrix, and every column it occupies is covered.
+pub fn select_row(m: &mut Matrix, node: NodeIndex) {
+    hide_row(m, node);
+    cover_node(m, node);
+    cover_row(m, node);
+}
+
+/// Undo `select_row()`.
+pub fn unselect_row(m: &mut Matrix, node: NodeIndex) {
+    uncover_row(m, node);
+    uncover_node(m, node);
+    unhide_row(m, node);
 }
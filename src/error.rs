@@ -0,0 +1,64 @@
+use std::error;
+use std::fmt;
+
+/// An `Error` is a problem with the actions or constraints given to a
+/// `Problem` or `Solver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<A, C> {
+    /// The action is not part of the problem.
+    UnknownAction(A),
+    /// The action can't be required, since `constraint` is already
+    /// satisfied by the actions required before, or requires a
+    /// different color.
+    Conflict { action: A, constraint: C },
+    /// The action has already been required.
+    AlreadyRequired(A),
+    /// The action has already been added to the problem.
+    DuplicateAction(A),
+    /// The action doesn't satisfy any constraint.
+    EmptyAction(A),
+    /// The action colors `constraint`, which is primary, or which the
+    /// action also satisfies without a color.
+    ColoredPrimary { action: A, constraint: C },
+    /// Text describing a problem is malformed at the given (1-indexed)
+    /// line.
+    Syntax { line: usize, message: String },
+    /// A checkpoint is malformed, or doesn't fit the solver it is
+    /// resumed with.
+    InvalidCheckpoint,
+    /// A sudoku grid must have n^2 cells, for a perfect square n.
+    InvalidSudokuSize(usize),
+    /// A sudoku grid has an entry too large for its size at the given
+    /// (0-indexed) row and column.
+    InvalidSudokuCell { row: usize, col: usize, value: usize }
+}
+
+impl<A: fmt::Debug, C: fmt::Debug> fmt::Display for Error<A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::UnknownAction(ref a) =>
+                write!(f, "unknown action {:?}", a),
+            Error::Conflict { ref action, ref constraint } =>
+                write!(f, "action {:?} conflicts with constraint {:?}, which is already full or has another color",
+                       action, constraint),
+            Error::AlreadyRequired(ref a) =>
+                write!(f, "action {:?} was already required", a),
+            Error::DuplicateAction(ref a) =>
+                write!(f, "action {:?} was already added", a),
+            Error::EmptyAction(ref a) =>
+                write!(f, "action {:?} satisfies no constraint", a),
+            Error::ColoredPrimary { ref action, ref constraint } =>
+                write!(f, "action {:?} colors constraint {:?}, which is not secondary", action, constraint),
+            Error::Syntax { line, ref message } =>
+                write!(f, "line {}: {}", line, message),
+            Error::InvalidCheckpoint =>
+                write!(f, "invalid checkpoint"),
+            Error::InvalidSudokuSize(n) =>
+                write!(f, "a sudoku grid can't have {} cells", n),
+            Error::InvalidSudokuCell { row, col, value } =>
+                write!(f, "invalid sudoku entry {} at ({}, {})", value, row, col)
+        }
+    }
+}
+
+impl<A: fmt::Debug, C: fmt::Debug> error::Error for Error<A, C> {}
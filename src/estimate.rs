@@ -0,0 +1,131 @@
+use node::Matrix;
+use problem::{Constraint, Action};
+use solver::{Solver, FrameState};
+use chooser::{ColumnChooser, choose_column};
+use rng::Rng;
+
+/// An `Estimate` of the size of a search tree, from Knuth's random
+/// probes (see `Solver::estimate()`).
+///
+/// `nodes` and `solutions` are averages over the probes, and are
+/// unbiased: their expected values are the number of nodes (as
+/// counted by `SearchStats::nodes`) and of solutions. The variances
+/// are those of a single probe; the variance of the averages is
+/// smaller by a factor of `probes`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub probes: usize,
+    pub nodes: f64,
+    pub nodes_variance: f64,
+    pub solutions: f64,
+    pub solutions_variance: f64
+}
+
+impl Estimate {
+    /// Return the standard error of the estimated number of nodes.
+    pub fn nodes_std_error(&self) -> f64 {
+        (self.nodes_variance / self.probes as f64).sqrt()
+    }
+
+    /// Return the standard error of the estimated number of solutions.
+    pub fn solutions_std_error(&self) -> f64 {
+        (self.solutions_variance / self.probes as f64).sqrt()
+    }
+}
+
+/// Running mean and variance of a sequence of values (Welford's
+/// method).
+#[derive(Debug, Clone, Copy, Default)]
+struct Moments {
+    n: f64,
+    mean: f64,
+    m2: f64
+}
+
+impl Moments {
+    fn add(&mut self, x: f64) {
+        self.n += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.n;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn variance(&self) -> f64 {
+        if self.n > 1.0 {
+            self.m2 / (self.n - 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Walk down a random path of the search, taking each branch with
+/// equal probability, and return the resulting estimates of the
+/// number of nodes and of solutions. The matrix is restored after.
+fn probe<C, H: ColumnChooser<C>>(m: &mut Matrix, names: &[C], chooser: &mut H, rng: &mut Rng) -> (f64, f64) {
+    let mut frames = Vec::new();
+    // The number of nodes at the current depth, if every node there
+    // branched as the ones on the path did.
+    let mut width = 1.0;
+    let mut nodes = 0.0;
+
+    let solutions = loop {
+        let c = match choose_column(m, names, chooser) {
+            None => break width,
+            Some(c) => c
+        };
+        if m.degree(c) == 0 {
+            break 0.0;
+        }
+
+        let k = m.branches(c);
+        width *= k as f64;
+        nodes += width;
+
+        // Branch i is searched with the rows before it already tried.
+        let mut frame = FrameState::new(m, c);
+        for _ in 0..=rng.below(k) {
+            frame.advance(m);
+        }
+        frames.push(frame);
+    };
+
+    for frame in frames.into_iter().rev() {
+        frame.finish(m);
+    }
+    (nodes, solutions)
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C> + Clone> Solver<A, C, H> {
+    /// Estimate the size of the search for every solution, from
+    /// `probes` random paths down the search tree, starting from
+    /// `seed`.
+    ///
+    /// Each probe costs about as much as finding one solution, so this
+    /// gives a quick idea of whether a full search is feasible. The
+    /// estimates are for the search as a whole, without splitting it
+    /// into independent components. They can be far off when the
+    /// tree is very unbalanced; the variances give an idea of how far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probes` is 0.
+    pub fn estimate(&self, probes: usize, seed: u64) -> Estimate {
+        assert!(probes > 0, "estimate needs at least one probe");
+        let mut m = self.problem.matrix.clone();
+        let mut chooser = self.chooser.clone();
+        let mut rng = Rng::new(seed);
+        let mut nodes = Moments::default();
+        let mut solutions = Moments::default();
+
+        for _ in 0..probes {
+            let (n, s) = probe(&mut m, &self.problem.constraints, &mut chooser, &mut rng);
+            nodes.add(n);
+            solutions.add(s);
+        }
+
+        Estimate { probes,
+                   nodes: nodes.mean, nodes_variance: nodes.variance(),
+                   solutions: solutions.mean, solutions_variance: solutions.variance() }
+    }
+}
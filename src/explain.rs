@@ -0,0 +1,130 @@
+use std::fmt;
+
+use iter::iter_full_row;
+use problem::{Problem, Constraint, Action};
+use solver::Solver;
+use chooser::ColumnChooser;
+use limits::SearchResult;
+
+/// An `Infeasibility` explains why a solver has no solution: its
+/// required `actions`, with only its `constraints` (all other
+/// constraints being dropped), are already unsatisfiable. See
+/// `Solver::explain_infeasibility()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Infeasibility<A, C> {
+    /// Required actions, in the order they were required.
+    pub actions: Vec<A>,
+    /// Constraints, in the order they were added to the problem.
+    pub constraints: Vec<C>,
+    /// True if dropping any one of the actions or constraints makes
+    /// the rest satisfiable. This only fails to hold if a search hit
+    /// the solver's limits, in which case the action or constraint
+    /// was kept.
+    pub minimal: bool
+}
+
+impl<A: fmt::Debug, C: fmt::Debug> fmt::Display for Infeasibility<A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.actions.is_empty() {
+            write!(f, "constraints {:?} can't be satisfied", self.constraints)
+        } else {
+            write!(f, "required actions {:?} leave constraints {:?} unsatisfiable", self.actions, self.constraints)
+        }
+    }
+}
+
+/// Return a copy of `problem` with only the constraints in `keep`, and
+/// the actions that satisfy any of them.
+fn restrict<A: Action, C: Constraint>(problem: &Problem<A, C>, keep: &[bool]) -> Problem<A, C> {
+    let m = &problem.matrix;
+    let mut p = Problem::new();
+    for (c, constraint) in problem.constraints.iter().enumerate() {
+        if keep[c] {
+            let b = m.bounds(c);
+            p.add_bounded_constraint(constraint, b.lo, b.hi);
+        }
+    }
+    for r in 0..problem.num_actions() {
+        let mut plain = Vec::new();
+        let mut colored = Vec::new();
+        for n in iter_full_row(m, m.row_start(r)) {
+            let c = m.column(n);
+            if !keep[c] {
+                continue;
+            }
+            match m.color(n) {
+                None => plain.push(problem.constraints[c].clone()),
+                Some(color) => colored.push((problem.constraints[c].clone(), color))
+            }
+        }
+        if !plain.is_empty() || !colored.is_empty() {
+            let added = p.add_colored_action(problem.get_action(r), &plain, &colored);
+            assert!(added.is_ok(), "actions are distinct and satisfy some constraint");
+        }
+    }
+    p
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C> + Clone> Solver<A, C, H> {
+    /// Search for a solution with only the given required actions and
+    /// constraints, under the limits of this solver.
+    fn solve_subset(&self, actions: &[A], keep: &[bool]) -> SearchResult<Vec<A>> {
+        let p = restrict(&self.problem, keep);
+        let mut solver = Solver::with_chooser(p, self.chooser.clone());
+        solver.set_limits(self.limits.clone());
+        for &a in actions {
+            // Actions left without constraints are satisfied anyway.
+            if solver.problem().get_action_row(a).is_some() && solver.require_action(a).is_err() {
+                return SearchResult::NoSolution;
+            }
+        }
+        solver.try_first_solution()
+    }
+
+    /// Explain why there is no solution, if there isn't: return a
+    /// subset of the required actions and of the constraints that is
+    /// unsatisfiable by itself, and minimal, so that dropping any
+    /// one of them leaves a satisfiable problem. For a sudoku with no
+    /// solution, the actions are clues that contradict each other.
+    ///
+    /// Returns None if there is a solution, or if the search for one
+    /// hit the solver's limits. Actions are dropped first, then
+    /// constraints, one at a time, with a search each time; the
+    /// solver's limits apply to each search.
+    pub fn explain_infeasibility(&self) -> Option<Infeasibility<A, C>> {
+        let mut keep = vec![true; self.problem.num_constraints()];
+        let mut actions = self.partial_solution.clone();
+        if self.solve_subset(&actions, &keep) != SearchResult::NoSolution {
+            return None;
+        }
+
+        let mut minimal = true;
+        let mut i = 0;
+        while i < actions.len() {
+            let a = actions.remove(i);
+            match self.solve_subset(&actions, &keep) {
+                SearchResult::NoSolution => continue,
+                SearchResult::GaveUp(_) => minimal = false,
+                SearchResult::Found(_) => {}
+            }
+            actions.insert(i, a);
+            i += 1;
+        }
+
+        for c in 0..keep.len() {
+            keep[c] = false;
+            match self.solve_subset(&actions, &keep) {
+                SearchResult::NoSolution => continue,
+                SearchResult::GaveUp(_) => minimal = false,
+                SearchResult::Found(_) => {}
+            }
+            keep[c] = true;
+        }
+
+        let constraints = self.problem.constraints.iter().zip(&keep)
+            .filter(|&(_, &k)| k)
+            .map(|(c, _)| c.clone())
+            .collect();
+        Some(Infeasibility { actions, constraints, minimal })
+    }
+}
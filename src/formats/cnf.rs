@@ -0,0 +1,262 @@
+//! Export to SAT, as a CNF formula in DIMACS format.
+//!
+//! Each action becomes a variable, numbered from 1 in the order the
+//! actions were added; further variables are introduced by the
+//! encodings. A constraint satisfied by exactly one action, as most
+//! are, becomes a clause requiring one of its actions, plus an
+//! at-most-one encoding chosen by `Encoding`. Other bounds use a
+//! sequential counter. Actions touching a colored constraint with
+//! different colors (or without one) exclude each other. The
+//! variables of actions touching no primary constraint are false
+//! unless they are required, matching the solver (see `Problem`).
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use node::{Matrix, Color, ColumnIndex};
+use iter::iter_full_row;
+use problem::{Problem, Constraint, Action};
+use solver::Solver;
+
+/// A literal: variable `v` is `v`, its negation `-v`.
+pub type Lit = i64;
+
+/// How to say that at most one of a set of variables is true.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// A clause for every pair of variables: no new variables, but
+    /// quadratically many clauses.
+    Pairwise,
+    /// Sinz's sequential counter: a new variable and three clauses per
+    /// variable.
+    Sequential,
+    /// Klieber and Kwon's commander encoding: groups of three, each
+    /// with a commander variable, recursively.
+    Commander
+}
+
+/// Groups of commander variables are encoded pairwise once they are
+/// this small.
+const COMMANDER_BASE: usize = 6;
+
+/// Number of variables per group in the commander encoding.
+const COMMANDER_GROUP: usize = 3;
+
+/// A CNF formula whose models are the solutions of a problem, as
+/// found by `Solver`.
+#[derive(Debug, Clone)]
+pub struct Cnf<A> {
+    actions: Vec<A>,
+    num_vars: usize,
+    clauses: Vec<Vec<Lit>>
+}
+
+impl<A: Action> Cnf<A> {
+    /// Encode a problem; rows required directly on the problem are
+    /// ignored.
+    pub fn from_problem<C: Constraint>(problem: &Problem<A, C>, encoding: Encoding) -> Cnf<A> {
+        Cnf::encode(problem, encoding, &[])
+    }
+
+    /// Encode the problem of a solver, along with its required
+    /// actions.
+    pub fn from_solver<C: Constraint, H>(solver: &Solver<A, C, H>, encoding: Encoding) -> Cnf<A> {
+        let mut cnf = Cnf::encode(&solver.problem, encoding, &solver.partial_solution);
+        for a in &solver.partial_solution {
+            let v = cnf.variable(*a).expect("required actions exist");
+            cnf.clauses.push(vec![v as Lit]);
+        }
+        cnf
+    }
+
+    fn encode<C: Constraint>(problem: &Problem<A, C>, encoding: Encoding, required: &[A]) -> Cnf<A> {
+        let actions: Vec<A> = (0..problem.num_actions()).map(|r| problem.get_action(r)).collect();
+        let mut cnf = Cnf { num_vars: actions.len(), actions, clauses: Vec::new() };
+
+        let m = &problem.matrix;
+        let mut columns: Vec<Vec<(Lit, Option<Color>)>> = vec![Vec::new(); m.num_columns()];
+        for r in 0..problem.num_actions() {
+            let mut primary = false;
+            for n in iter_full_row(m, m.row_start(r)) {
+                columns[m.column(n)].push((r as Lit + 1, m.color(n)));
+                primary |= m.bounds(m.column(n)).is_primary();
+            }
+            if !primary && !required.contains(&cnf.actions[r]) {
+                cnf.clauses.push(vec![-(r as Lit) - 1]);
+            }
+        }
+        for (c, entries) in columns.iter().enumerate() {
+            cnf.constraint(m, c, entries, encoding);
+        }
+        cnf
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn clauses(&self) -> &[Vec<Lit>] {
+        &self.clauses
+    }
+
+    /// Return the variable of an action, if it exists.
+    pub fn variable(&self, action: A) -> Option<usize> {
+        self.actions.iter().position(|a| *a == action).map(|i| i + 1)
+    }
+
+    /// Return the actions set by a model of the formula, given as
+    /// the literals it makes true (as in a SAT solver's `v` lines).
+    pub fn solution(&self, model: &[Lit]) -> Vec<A> {
+        model.iter()
+            .filter(|&&l| l > 0 && l as usize <= self.actions.len())
+            .map(|&l| self.actions[l as usize - 1])
+            .collect()
+    }
+
+    /// Write the formula in DIMACS format.
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "p cnf {} {}", self.num_vars, self.clauses.len())?;
+        for clause in &self.clauses {
+            for l in clause {
+                write!(out, "{} ", l)?;
+            }
+            writeln!(out, "0")?;
+        }
+        Ok(())
+    }
+
+    pub fn to_dimacs(&self) -> String {
+        let mut out = Vec::new();
+        self.write(&mut out).expect("writing to memory");
+        String::from_utf8(out).expect("DIMACS is ASCII")
+    }
+
+    fn new_var(&mut self) -> Lit {
+        self.num_vars += 1;
+        self.num_vars as Lit
+    }
+
+    /// Encode the bounds and colors of column `c`.
+    fn constraint(&mut self, m: &Matrix, c: ColumnIndex, entries: &[(Lit, Option<Color>)], encoding: Encoding) {
+        let b = m.bounds(c);
+        let plain: Vec<Lit> = entries.iter().filter(|e| e.1.is_none()).map(|e| e.0).collect();
+
+        let mut colors: BTreeMap<Color, Vec<Lit>> = BTreeMap::new();
+        for &(l, color) in entries {
+            if let Some(color) = color {
+                colors.entry(color).or_default().push(l);
+            }
+        }
+        if !colors.is_empty() {
+            // One variable per color, true if the column has that
+            // color; at most one of them, and none with a plain row.
+            let mut chosen = Vec::new();
+            for lits in colors.values() {
+                let y = self.new_var();
+                for l in lits {
+                    self.clauses.push(vec![-l, y]);
+                }
+                for l in &plain {
+                    self.clauses.push(vec![-l, -y]);
+                }
+                chosen.push(y);
+            }
+            self.at_most_one(&chosen, encoding);
+        }
+
+        if b.hi == 1 {
+            if b.lo == 1 {
+                self.clauses.push(plain.clone());
+            }
+            self.at_most_one(&plain, encoding);
+        } else {
+            self.at_most(&plain, b.hi);
+            let negated: Vec<Lit> = plain.iter().map(|l| -l).collect();
+            match plain.len().checked_sub(b.lo) {
+                Some(k) => self.at_most(&negated, k),
+                None => self.clauses.push(Vec::new())
+            }
+        }
+    }
+
+    fn at_most_one(&mut self, lits: &[Lit], encoding: Encoding) {
+        match encoding {
+            Encoding::Pairwise => self.pairwise(lits),
+            Encoding::Sequential => self.at_most(lits, 1),
+            Encoding::Commander => self.commander(lits)
+        }
+    }
+
+    fn pairwise(&mut self, lits: &[Lit]) {
+        for (i, a) in lits.iter().enumerate() {
+            for b in &lits[i + 1..] {
+                self.clauses.push(vec![-a, -b]);
+            }
+        }
+    }
+
+    fn commander(&mut self, lits: &[Lit]) {
+        if lits.len() <= COMMANDER_BASE {
+            return self.pairwise(lits);
+        }
+        let mut commanders = Vec::new();
+        for group in lits.chunks(COMMANDER_GROUP) {
+            let c = self.new_var();
+            self.pairwise(group);
+            // The commander is true iff one of its group is.
+            let mut any = vec![-c];
+            for l in group {
+                self.clauses.push(vec![-l, c]);
+                any.push(*l);
+            }
+            self.clauses.push(any);
+            commanders.push(c);
+        }
+        self.commander(&commanders);
+    }
+
+    /// Sinz's sequential counter: `s[i][j]` is true if at least
+    /// `j + 1` of the first `i + 1` literals are.
+    fn at_most(&mut self, lits: &[Lit], k: usize) {
+        let n = lits.len();
+        if k >= n {
+            return;
+        }
+        if k == 0 {
+            for l in lits {
+                self.clauses.push(vec![-l]);
+            }
+            return;
+        }
+
+        let s: Vec<Vec<Lit>> = (0..n - 1).map(|_| (0..k).map(|_| self.new_var()).collect()).collect();
+        self.clauses.push(vec![-lits[0], s[0][0]]);
+        for v in &s[0][1..] {
+            self.clauses.push(vec![-v]);
+        }
+        for i in 1..n - 1 {
+            self.clauses.push(vec![-lits[i], s[i][0]]);
+            self.clauses.push(vec![-s[i - 1][0], s[i][0]]);
+            for j in 1..k {
+                self.clauses.push(vec![-lits[i], -s[i - 1][j - 1], s[i][j]]);
+                self.clauses.push(vec![-s[i - 1][j], s[i][j]]);
+            }
+            self.clauses.push(vec![-lits[i], -s[i - 1][k - 1]]);
+        }
+        self.clauses.push(vec![-lits[n - 1], -s[n - 2][k - 1]]);
+    }
+}
+
+/// Read the literals of a model from a SAT solver's output: the `v`
+/// lines if there are any, or else every number, ignoring comments,
+/// the `s` line and the terminating 0.
+pub fn parse_model(output: &str) -> Vec<Lit> {
+    let lines: Vec<&str> = output.lines().map(|l| l.trim()).collect();
+    let has_v = lines.iter().any(|l| l.starts_with('v'));
+    lines.iter()
+        .filter(|l| if has_v { l.starts_with('v') } else { !l.starts_with('c') && !l.starts_with('s') })
+        .flat_map(|l| l.trim_start_matches('v').split_whitespace())
+        .filter_map(|t| t.parse::<Lit>().ok())
+        .filter(|&l| l != 0)
+        .collect()
+}
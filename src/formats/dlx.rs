@@ -0,0 +1,197 @@
+//! Knuth's text format for exact cover problems, as read by his DLX
+//! programs.
+//!
+//! Lines starting with `|` are comments. The first other line lists
+//! the primary items, then `|`, then the secondary items. A primary
+//! item that must be covered between `u` and `v` times is written
+//! `u:v|name` (or `v|name` if `u` and `v` are equal). Every following
+//! line is an option: the items it covers, where `name:color` gives a
+//! secondary item a color.
+//!
+//! ```text
+//! | A tiny problem
+//! a b c | x
+//! a b
+//! c x:red
+//! a c x:blue
+//! b
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use node::Color;
+use iter::iter_full_row;
+use problem::{Problem, Constraint, Action};
+use error::Error;
+
+pub type DlxProblem = Problem<usize, String>;
+pub type DlxError = Error<usize, String>;
+
+fn syntax(line: usize, message: String) -> DlxError {
+    Error::Syntax { line, message }
+}
+
+/// Parse the bounds prefix of a primary item: `u:v|name` or `v|name`.
+fn parse_item(token: &str, line: usize) -> Result<(String, usize, usize), DlxError> {
+    let (bounds, name) = match token.find('|') {
+        Some(i) => (Some(&token[..i]), &token[i + 1..]),
+        None => (None, token)
+    };
+    if name.is_empty() || name.contains(':') {
+        return Err(syntax(line, format!("invalid item name {:?}", token)));
+    }
+
+    let number = |s: &str| s.parse::<usize>().map_err(|_| syntax(line, format!("invalid bounds in {:?}", token)));
+    let (lo, hi) = match bounds {
+        None => (1, 1),
+        Some(b) => match b.find(':') {
+            Some(i) => (number(&b[..i])?, number(&b[i + 1..])?),
+            None => {
+                let v = number(b)?;
+                (v, v)
+            }
+        }
+    };
+    if hi == 0 || lo > hi {
+        return Err(syntax(line, format!("invalid bounds in {:?}", token)));
+    }
+    Ok((name.to_string(), lo, hi))
+}
+
+/// Parse a problem in Knuth's DLX format. Each option becomes an
+/// action numbered by its position (from 0), and each item a
+/// constraint named after it. Color names are numbered in order of
+/// appearance.
+pub fn parse(input: &str) -> Result<DlxProblem, DlxError> {
+    parse_named(input).map(|(p, _)| p)
+}
+
+/// Parse a problem like `parse()`, also returning each option as
+/// written, to name the actions.
+pub fn parse_named(input: &str) -> Result<(DlxProblem, Vec<String>), DlxError> {
+    let mut lines = input.lines().enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|&(_, l)| !l.is_empty() && !l.starts_with('|'));
+
+    let mut p = Problem::new();
+    let (line, header) = lines.next().ok_or_else(|| syntax(0, "no items".to_string()))?;
+    let mut secondary = false;
+    for token in header.split_whitespace() {
+        if token == "|" {
+            if secondary {
+                return Err(syntax(line, "more than one '|' in the items".to_string()));
+            }
+            secondary = true;
+            continue;
+        }
+        let (name, lo, hi) = if secondary {
+            if token.contains(':') || token.contains('|') {
+                return Err(syntax(line, format!("invalid item name {:?}", token)));
+            }
+            (token.to_string(), 0, 1)
+        } else {
+            parse_item(token, line)?
+        };
+        if p.constraint_bounds(&name).is_some() {
+            return Err(syntax(line, format!("item {:?} listed twice", name)));
+        }
+        p.add_bounded_constraint(&name, lo, hi);
+    }
+
+    let mut colors: HashMap<String, Color> = HashMap::new();
+    let mut names = Vec::new();
+    for (action, (line, option)) in lines.enumerate() {
+        let mut plain = Vec::new();
+        let mut colored = Vec::new();
+        for token in option.split_whitespace() {
+            let (name, color) = match token.find(':') {
+                Some(i) => (&token[..i], Some(&token[i + 1..])),
+                None => (token, None)
+            };
+            let name = name.to_string();
+            if p.constraint_bounds(&name).is_none() {
+                return Err(syntax(line, format!("unknown item {:?}", name)));
+            }
+            if plain.contains(&name) || colored.iter().any(|(c, _)| *c == name) {
+                return Err(syntax(line, format!("item {:?} repeated in option", name)));
+            }
+            match color {
+                None => plain.push(name),
+                Some(color) => {
+                    if color.is_empty() || !p.is_secondary(&name) {
+                        return Err(syntax(line, format!("invalid color in {:?}", token)));
+                    }
+                    let n = colors.len();
+                    colored.push((name, *colors.entry(color.to_string()).or_insert(n)));
+                }
+            }
+        }
+        p.add_colored_action(action, &plain, &colored)?;
+        names.push(option.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+    Ok((p, names))
+}
+
+/// Return an error if `name` can't be written as an item name.
+fn check_name(name: &str) -> io::Result<()> {
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '|' || c == ':') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("invalid item name {:?}", name)));
+    }
+    Ok(())
+}
+
+/// Write a problem in Knuth's DLX format, naming each item after its
+/// constraint, and writing colors as numbers. Options are written in
+/// the order their actions were added, without the actions
+/// themselves; any required actions are ignored.
+///
+/// Constraint names must not be empty, nor contain whitespace, `|`
+/// or `:`, and there must be at least one constraint that isn't a
+/// plain secondary one, as the format requires a primary item.
+pub fn write<A: Action, C: Constraint + Display, W: Write>(problem: &Problem<A, C>, out: &mut W) -> io::Result<()> {
+    let m = &problem.matrix;
+    let names: Vec<String> = problem.constraints.iter().map(|c| c.to_string()).collect();
+    for name in &names {
+        check_name(name)?;
+    }
+
+    let mut primary = Vec::new();
+    let mut secondary = Vec::new();
+    for (c, name) in names.iter().enumerate() {
+        let b = m.bounds(c);
+        match (b.lo, b.hi) {
+            (0, 1) => secondary.push(name.clone()),
+            (1, 1) => primary.push(name.clone()),
+            (lo, hi) if lo == hi => primary.push(format!("{}|{}", hi, name)),
+            (lo, hi) => primary.push(format!("{}:{}|{}", lo, hi, name))
+        }
+    }
+    if primary.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "no primary items"));
+    }
+    write!(out, "{}", primary.join(" "))?;
+    if !secondary.is_empty() {
+        write!(out, " | {}", secondary.join(" "))?;
+    }
+    writeln!(out)?;
+
+    for r in 0..problem.num_actions() {
+        let items: Vec<String> = iter_full_row(m, m.row_start(r))
+            .map(|n| match m.color(n) {
+                None => names[m.column(n)].clone(),
+                Some(color) => format!("{}:{}", names[m.column(n)], color)
+            })
+            .collect();
+        writeln!(out, "{}", items.join(" "))?;
+    }
+    Ok(())
+}
+
+/// Return a problem in Knuth's DLX format; see `write()`.
+pub fn to_string<A: Action, C: Constraint + Display>(problem: &Problem<A, C>) -> io::Result<String> {
+    let mut out = Vec::new();
+    write(problem, &mut out)?;
+    Ok(String::from_utf8(out).expect("item names are strings"))
+}
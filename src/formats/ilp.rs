@@ -0,0 +1,345 @@
+//! Export to integer linear programming, in CPLEX LP and free MPS
+//! formats.
+//!
+//! Each action becomes a binary variable `x<i>`, numbered from 0 in
+//! the order the actions were added. A constraint becomes a row `c<j>`
+//! (numbered in the order the constraints were added) bounding the
+//! number of its actions: an equality for a primary constraint
+//! satisfied exactly once or a given number of times, inequalities
+//! otherwise. A colored constraint gets a binary variable `y<j>_<k>`
+//! per color, true if it has that color, with rows requiring at most
+//! one color and excluding the actions without one. If any action has
+//! a cost, the objective is to minimize the total cost; costs must fit
+//! in an `i64` to be written.
+//!
+//! As in the solver, actions touching no primary constraint can only be
+//! chosen if they are required (see `Problem`): their variables are
+//! otherwise fixed to 0.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{self, Write};
+
+use node::{Matrix, Color, ColumnIndex};
+use iter::iter_full_row;
+use problem::{Problem, Constraint, Action};
+use solver::Solver;
+use error::Error;
+
+/// The error returned by `Ilp::parse_solution()`.
+pub type IlpError<A> = Error<A, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sense {
+    Equal,
+    AtMost,
+    AtLeast
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    name: String,
+    terms: Vec<(usize, i64)>,
+    sense: Sense,
+    rhs: i64
+}
+
+/// A 0-1 integer linear program whose feasible points are the
+/// solutions of a problem, as found by `Solver`.
+#[derive(Debug, Clone)]
+pub struct Ilp<A> {
+    actions: Vec<A>,
+    costs: Vec<u64>,
+    /// The names of all variables, actions first.
+    vars: Vec<String>,
+    rows: Vec<Row>,
+    /// Variables fixed to a value.
+    fixed: Vec<(usize, i64)>
+}
+
+impl<A: Action> Ilp<A> {
+    /// Encode a problem; rows required directly on the problem are
+    /// ignored.
+    pub fn from_problem<C: Constraint>(problem: &Problem<A, C>) -> Ilp<A> {
+        Ilp::encode(problem, &[])
+    }
+
+    /// Encode the problem of a solver, fixing its required actions
+    /// to 1.
+    pub fn from_solver<C: Constraint, H>(solver: &Solver<A, C, H>) -> Ilp<A> {
+        let mut ilp = Ilp::encode(&solver.problem, &solver.partial_solution);
+        for a in &solver.partial_solution {
+            let v = ilp.variable(*a).expect("required actions exist");
+            ilp.fixed.push((v, 1));
+        }
+        ilp
+    }
+
+    fn encode<C: Constraint>(problem: &Problem<A, C>, required: &[A]) -> Ilp<A> {
+        let n = problem.num_actions();
+        let mut ilp = Ilp {
+            actions: (0..n).map(|r| problem.get_action(r)).collect(),
+            costs: (0..n).map(|r| problem.get_cost(r)).collect(),
+            vars: (0..n).map(|r| format!("x{}", r)).collect(),
+            rows: Vec::new(),
+            fixed: Vec::new()
+        };
+
+        let m = &problem.matrix;
+        let mut columns: Vec<Vec<(usize, Option<Color>)>> = vec![Vec::new(); m.num_columns()];
+        for r in 0..n {
+            let mut primary = false;
+            for node in iter_full_row(m, m.row_start(r)) {
+                columns[m.column(node)].push((r, m.color(node)));
+                primary |= m.bounds(m.column(node)).is_primary();
+            }
+            if !primary && !required.contains(&ilp.actions[r]) {
+                ilp.fixed.push((r, 0));
+            }
+        }
+        for (c, entries) in columns.iter().enumerate() {
+            ilp.constraint(m, c, entries);
+        }
+        ilp
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Return the index of the variable of an action, if it exists;
+    /// it is named `x` followed by that index.
+    pub fn variable(&self, action: A) -> Option<usize> {
+        self.actions.iter().position(|a| *a == action)
+    }
+
+    /// Return the actions set by a solution vector, given as the
+    /// values of the variables in order (at least one per action).
+    /// Values are rounded, so solvers' tolerances don't matter.
+    pub fn solution(&self, values: &[f64]) -> Vec<A> {
+        self.actions.iter().zip(values)
+            .filter(|&(_, &v)| v > 0.5)
+            .map(|(a, _)| *a)
+            .collect()
+    }
+
+    /// Read the actions set by a solver's solution file: lines
+    /// holding a variable name and its value, as written by most MIP
+    /// solvers (Gurobi's `.sol`, CBC, HiGHS...). Other variables,
+    /// blank lines and comments starting with `#` are ignored, as are
+    /// leading column numbers and trailing fields.
+    pub fn parse_solution(&self, input: &str) -> Result<Vec<A>, IlpError<A>> {
+        let mut values = vec![0.0; self.actions.len()];
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let at = match fields.iter().position(|f| self.action_index(f).is_some()) {
+                Some(at) => at,
+                None => continue
+            };
+            let value = fields.get(at + 1)
+                .and_then(|v| v.parse::<f64>().ok())
+                .ok_or_else(|| Error::Syntax { line: i + 1, message: format!("no value for {}", fields[at]) })?;
+            values[self.action_index(fields[at]).unwrap()] = value;
+        }
+        Ok(self.solution(&values))
+    }
+
+    fn action_index(&self, name: &str) -> Option<usize> {
+        let i = name.strip_prefix('x')?.parse::<usize>().ok()?;
+        if i < self.actions.len() && self.vars[i] == name { Some(i) } else { None }
+    }
+
+    /// Return the terms of the objective, or an error if a cost is too
+    /// large to write.
+    fn objective(&self) -> io::Result<Vec<(usize, i64)>> {
+        self.costs.iter().enumerate()
+            .filter(|&(_, &c)| c > 0)
+            .map(|(v, &c)| i64::try_from(c).map(|c| (v, c)).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("cost {} of {} is too large", c, self.vars[v]))
+            }))
+            .collect()
+    }
+
+    fn new_var(&mut self, name: String) -> usize {
+        self.vars.push(name);
+        self.vars.len() - 1
+    }
+
+    fn row(&mut self, name: String, terms: Vec<(usize, i64)>, sense: Sense, rhs: i64) {
+        self.rows.push(Row { name, terms, sense, rhs });
+    }
+
+    /// Encode the bounds and colors of column `c`.
+    fn constraint(&mut self, m: &Matrix, c: ColumnIndex, entries: &[(usize, Option<Color>)]) {
+        let b = m.bounds(c);
+        let plain: Vec<(usize, i64)> = entries.iter().filter(|e| e.1.is_none()).map(|e| (e.0, 1)).collect();
+        let (lo, hi) = (b.lo as i64, b.hi as i64);
+
+        if b.lo == b.hi {
+            self.row(format!("c{}", c), plain.clone(), Sense::Equal, hi);
+        } else {
+            if b.lo > 0 {
+                self.row(format!("c{}_lo", c), plain.clone(), Sense::AtLeast, lo);
+            }
+            if b.hi < plain.len() {
+                self.row(format!("c{}", c), plain.clone(), Sense::AtMost, hi);
+            }
+        }
+
+        let mut colors: BTreeMap<Color, Vec<usize>> = BTreeMap::new();
+        for &(r, color) in entries {
+            if let Some(color) = color {
+                colors.entry(color).or_default().push(r);
+            }
+        }
+        if colors.is_empty() {
+            return;
+        }
+        // One variable per color, at least each of its actions; at
+        // most one of them, and none along with a plain action.
+        let mut chosen = Vec::new();
+        for (color, rows) in colors {
+            let y = self.new_var(format!("y{}_{}", c, color));
+            for r in rows {
+                self.row(format!("c{}_x{}", c, r), vec![(r, 1), (y, -1)], Sense::AtMost, 0);
+            }
+            chosen.push((y, 1));
+        }
+        for &(r, _) in &plain {
+            let mut terms = chosen.clone();
+            terms.push((r, 1));
+            self.row(format!("c{}_x{}", c, r), terms, Sense::AtMost, 1);
+        }
+        if plain.is_empty() && chosen.len() > 1 {
+            self.row(format!("c{}_colors", c), chosen, Sense::AtMost, 1);
+        }
+    }
+
+    fn write_terms<W: Write>(&self, out: &mut W, terms: &[(usize, i64)]) -> io::Result<()> {
+        if terms.is_empty() {
+            // Keep the row well-formed; it can't be satisfied anyway.
+            return write!(out, " 0 {}", self.vars.first().map_or("x0", |v| v.as_str()));
+        }
+        for (i, &(v, coef)) in terms.iter().enumerate() {
+            match (i, coef < 0) {
+                (0, false) => write!(out, " ")?,
+                (0, true) => write!(out, " -")?,
+                (_, false) => write!(out, " + ")?,
+                (_, true) => write!(out, " - ")?
+            }
+            if coef.abs() != 1 {
+                write!(out, "{} ", coef.abs())?;
+            }
+            write!(out, "{}", self.vars[v])?;
+        }
+        Ok(())
+    }
+
+    /// Write the program in CPLEX LP format.
+    pub fn write_lp<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let objective = self.objective()?;
+        writeln!(out, "Minimize")?;
+        write!(out, " obj:")?;
+        if !objective.is_empty() {
+            self.write_terms(out, &objective)?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "Subject To")?;
+        for row in &self.rows {
+            write!(out, " {}:", row.name)?;
+            self.write_terms(out, &row.terms)?;
+            let sense = match row.sense {
+                Sense::Equal => "=",
+                Sense::AtMost => "<=",
+                Sense::AtLeast => ">="
+            };
+            writeln!(out, " {} {}", sense, row.rhs)?;
+        }
+
+        if !self.fixed.is_empty() {
+            writeln!(out, "Bounds")?;
+            for &(v, value) in &self.fixed {
+                writeln!(out, " {} = {}", self.vars[v], value)?;
+            }
+        }
+        writeln!(out, "Binaries")?;
+        for v in &self.vars {
+            writeln!(out, " {}", v)?;
+        }
+        writeln!(out, "End")
+    }
+
+    /// Write the program in free MPS format.
+    pub fn write_mps<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let objective = self.objective()?;
+        writeln!(out, "NAME exact_cover")?;
+        writeln!(out, "ROWS")?;
+        writeln!(out, " N obj")?;
+        for row in &self.rows {
+            let sense = match row.sense {
+                Sense::Equal => "E",
+                Sense::AtMost => "L",
+                Sense::AtLeast => "G"
+            };
+            writeln!(out, " {} {}", sense, row.name)?;
+        }
+
+        // Columns list their entries variable by variable.
+        let mut entries: Vec<Vec<(&str, i64)>> = vec![Vec::new(); self.vars.len()];
+        for (v, cost) in objective {
+            entries[v].push(("obj", cost));
+        }
+        for row in &self.rows {
+            for &(v, coef) in &row.terms {
+                entries[v].push((&row.name, coef));
+            }
+        }
+        writeln!(out, "COLUMNS")?;
+        writeln!(out, " MARKER 'MARKER' 'INTORG'")?;
+        for (v, column) in entries.iter().enumerate() {
+            if column.is_empty() {
+                // Still declare the variable.
+                writeln!(out, " {} obj 0", self.vars[v])?;
+            }
+            for &(row, coef) in column {
+                writeln!(out, " {} {} {}", self.vars[v], row, coef)?;
+            }
+        }
+        writeln!(out, " MARKER 'MARKER' 'INTEND'")?;
+
+        writeln!(out, "RHS")?;
+        for row in self.rows.iter().filter(|r| r.rhs != 0) {
+            writeln!(out, " rhs {} {}", row.name, row.rhs)?;
+        }
+        writeln!(out, "BOUNDS")?;
+        for (v, name) in self.vars.iter().enumerate() {
+            if let Some(&(_, value)) = self.fixed.iter().find(|f| f.0 == v) {
+                writeln!(out, " FX bnd {} {}", name, value)?;
+            } else {
+                writeln!(out, " BV bnd {}", name)?;
+            }
+        }
+        writeln!(out, "ENDATA")
+    }
+
+    pub fn to_lp(&self) -> io::Result<String> {
+        let mut out = Vec::new();
+        self.write_lp(&mut out)?;
+        Ok(String::from_utf8(out).expect("LP is ASCII"))
+    }
+
+    pub fn to_mps(&self) -> io::Result<String> {
+        let mut out = Vec::new();
+        self.write_mps(&mut out)?;
+        Ok(String::from_utf8(out).expect("MPS is ASCII"))
+    }
+}
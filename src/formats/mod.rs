@@ -0,0 +1,4 @@
+pub mod dlx;
+pub mod cnf;
+pub mod ilp;
+pub mod simple;
@@ -0,0 +1,60 @@
+//! A simple text format: one action per line, named before a colon
+//! and followed by the constraints it satisfies, all primary.
+//!
+//! ```text
+//! # The example from Wikipedia
+//! A: 1 4 7
+//! B: 1 4
+//! C: 4 5 7
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. Since actions
+//! must be `Copy`, they are numbered by position (from 0), and their
+//! names returned alongside the problem.
+
+use problem::Problem;
+use error::Error;
+
+pub type SimpleProblem = Problem<usize, String>;
+pub type SimpleError = Error<usize, String>;
+
+fn syntax(line: usize, message: String) -> SimpleError {
+    Error::Syntax { line, message }
+}
+
+/// Parse a problem in the simple format, returning it along with the
+/// name of each action.
+pub fn parse(input: &str) -> Result<(SimpleProblem, Vec<String>), SimpleError> {
+    let mut p = Problem::new();
+    let mut names: Vec<String> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, rest) = match line.find(':') {
+            Some(at) => (line[..at].trim(), &line[at + 1..]),
+            None => return Err(syntax(i + 1, "expected \"action: constraints\"".to_string()))
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(syntax(i + 1, format!("invalid action name {:?}", name)));
+        }
+        if names.iter().any(|n| n == name) {
+            return Err(syntax(i + 1, format!("action {:?} listed twice", name)));
+        }
+
+        let mut constraints: Vec<String> = Vec::new();
+        for c in rest.split_whitespace() {
+            if constraints.iter().any(|d| d == c) {
+                return Err(syntax(i + 1, format!("constraint {:?} repeated", c)));
+            }
+            constraints.push(c.to_string());
+        }
+        if constraints.is_empty() {
+            return Err(syntax(i + 1, format!("action {:?} satisfies no constraint", name)));
+        }
+        p.add_action(names.len(), &constraints)?;
+        names.push(name.to_string());
+    }
+    Ok((p, names))
+}
@@ -1,10 +1,15 @@
 use problem::{Problem};
 use solver::{Solver};
+use error::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 /// A `SudokuAction` is filling a particular square (identified by
 /// `row` and `column`) with a particular number. An nxn sudoku will
 /// have n^3 possible actions, of which n^2 form a particular solution.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SudokuAction {
     pub cell: usize,
     pub row: usize,
@@ -13,6 +18,7 @@ pub struct SudokuAction {
 
 /// Constraint `Location`s are 0-indexed.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Location {
     Row(usize),
     Col(usize),
@@ -22,6 +28,7 @@ pub enum Location {
 /// A `Constraint` encodes the existence and uniqueness constraints
 /// that a sudoku solution must satisfy.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SudokuConstraint {
     /// Existence constraints enforce that every square must be filled in.
     Existence(usize, usize),
@@ -46,7 +53,9 @@ impl SudokuAction {
 
 pub type SudokuProblem = Problem<SudokuAction, SudokuConstraint>;
 pub type SudokuSolver = Solver<SudokuAction, SudokuConstraint>;
+pub type SudokuError = Error<SudokuAction, SudokuConstraint>;
 
+#[allow(clippy::needless_return)]
 fn isqrt(n: usize) -> usize {
     //// n is typically small.
     for i in 1..(n+1) {
@@ -70,26 +79,31 @@ pub fn sudoku_problem(n: usize) -> Option<SudokuProblem> {
         for row in 0..n {
             for col in 0..n {
                 let a = SudokuAction::new(i, row, col);
-                p.add_action(a, &a.constraints(box_size));
+                p.add_action(a, &a.constraints(box_size)).expect("sudoku actions are distinct");
             }
         }
     }
     Some(p)
 }
 
-/// Return a solver for a partially-filled sudoku problem.
-pub fn sudoku_solver(cells: &[usize]) -> Result<SudokuSolver, String> {
+/// Return a solver for a partially-filled sudoku problem, given its
+/// cells row by row, with 0 for an empty cell.
+///
+/// Returns an error if the grid has the wrong number of cells, if an
+/// entry is too large, or if two entries conflict.
+pub fn sudoku_solver(cells: &[usize]) -> Result<SudokuSolver, SudokuError> {
     // Verify the problem.
     let n = cells.len();
     let psize = isqrt(n);
-    if psize * psize != n {
-        return Err("Not a square array".to_string());
+    let box_size = isqrt(psize);
+    if psize == 0 || psize * psize != n || box_size * box_size != psize {
+        return Err(Error::InvalidSudokuSize(n));
     }
 
     for (i, arr) in cells.chunks(psize).enumerate() {
         for (j, x) in arr.iter().enumerate() {
-            if  *x > psize {
-                return Err(format!("Invalid entry at ({}, {}): {} >= {}", i, j, *x, psize).to_string())
+            if *x > psize {
+                return Err(Error::InvalidSudokuCell { row: i, col: j, value: *x });
             }
         }
     }
@@ -102,10 +116,7 @@ pub fn sudoku_solver(cells: &[usize]) -> Result<SudokuSolver, String> {
     for (i, arr) in cells.chunks(psize).enumerate() {
         for (j, x) in arr.iter().enumerate() {
             if *x != 0 {
-                let a =  SudokuAction::new(*x, i, j);
-                if s.require_action(a).is_err() {
-                    return Err(format!("Could not require action {:?}", a).to_string());
-                }
+                s.require_action(SudokuAction::new(*x, i, j))?;
             }
         }
     }
@@ -1,29 +1,28 @@
-use node::{WeakNode, OwnedNode};
+use node::{Matrix, NodeIndex, ColumnIndex};
 
+/// Iterate through the nodes in a row to the right (in normal order)
+/// until hitting the original node. Does not hit the first node.
 #[derive(Debug)]
-pub struct RowIterator {
-    curr_right: OwnedNode,
-    curr_left: OwnedNode
+pub struct RowIterator<'a> {
+    matrix: &'a Matrix,
+    curr_right: NodeIndex,
+    curr_left: NodeIndex
 }
 
-/// Iterate through them to the right (in normal order)
-/// until hitting the original node. Does not hit the first node.
-impl RowIterator {
-    fn new(node: &WeakNode) -> RowIterator {
-        let st = node.upgrade().unwrap();
-        RowIterator { curr_right: st.clone(), curr_left: st }
+impl<'a> RowIterator<'a> {
+    fn new(matrix: &'a Matrix, node: NodeIndex) -> RowIterator<'a> {
+        RowIterator { matrix, curr_right: node, curr_left: node }
     }
 }
 
-impl Iterator for RowIterator {
-    type Item = WeakNode;
+impl<'a> Iterator for RowIterator<'a> {
+    type Item = NodeIndex;
 
-    fn next(&mut self) -> Option<WeakNode> {
-        let ref weak_next = self.curr_right.borrow().right().clone();
-        self.curr_right = weak_next.upgrade().unwrap();
+    fn next(&mut self) -> Option<NodeIndex> {
+        self.curr_right = self.matrix.right(self.curr_right);
 
-        if self.curr_right.borrow().column != self.curr_left.borrow().column {
-            Some(weak_next.clone())
+        if self.curr_right != self.curr_left {
+            Some(self.curr_right)
         }
         else {
             None
@@ -31,13 +30,12 @@ impl Iterator for RowIterator {
     }
 }
 
-impl DoubleEndedIterator for RowIterator {
-    fn next_back(&mut self) -> Option<WeakNode> {
-        let ref weak_next = self.curr_left.borrow().left().clone();
-        self.curr_left = weak_next.upgrade().unwrap();
+impl<'a> DoubleEndedIterator for RowIterator<'a> {
+    fn next_back(&mut self) -> Option<NodeIndex> {
+        self.curr_left = self.matrix.left(self.curr_left);
 
-        if self.curr_right.borrow().column != self.curr_left.borrow().column {
-            Some(weak_next.clone())
+        if self.curr_right != self.curr_left {
+            Some(self.curr_left)
         }
         else {
             None
@@ -45,31 +43,30 @@ impl DoubleEndedIterator for RowIterator {
     }
 }
 
+/// Iterate through the nodes in a column, skipping the initial
+/// (header) node.
 #[derive(Debug)]
-pub struct ColumnIterator {
-    curr_up: OwnedNode,
-    curr_down: OwnedNode
+pub struct ColumnIterator<'a> {
+    matrix: &'a Matrix,
+    curr_up: NodeIndex,
+    curr_down: NodeIndex
 }
 
-
-/// Iterate through the ndoes in a column, skipping the initial
-/// (header) node.
-impl ColumnIterator {
-    pub fn new(c: &OwnedNode) -> ColumnIterator {
-        ColumnIterator { curr_up: c.clone(),
-                         curr_down: c.clone() }
+impl<'a> ColumnIterator<'a> {
+    pub fn new(matrix: &'a Matrix, c: ColumnIndex) -> ColumnIterator<'a> {
+        let h = matrix.header(c);
+        ColumnIterator { matrix, curr_up: h, curr_down: h }
     }
 }
 
-impl Iterator for ColumnIterator {
-    type Item = WeakNode;
+impl<'a> Iterator for ColumnIterator<'a> {
+    type Item = NodeIndex;
 
-    fn next(&mut self) -> Option<WeakNode> {
-        let ref weak_next = self.curr_down.borrow().down();
-        self.curr_down = weak_next.upgrade().unwrap();
+    fn next(&mut self) -> Option<NodeIndex> {
+        self.curr_down = self.matrix.down(self.curr_down);
 
-        if self.curr_down.borrow().get_row() != self.curr_up.borrow().get_row() {
-            Some(weak_next.clone())
+        if self.curr_down != self.curr_up {
+            Some(self.curr_down)
         }
         else {
             None
@@ -77,13 +74,12 @@ impl Iterator for ColumnIterator {
     }
 }
 
-impl DoubleEndedIterator for ColumnIterator {
-    fn next_back(&mut self) -> Option<WeakNode> {
-        let ref weak_next = self.curr_up.borrow().up();
-        self.curr_up = weak_next.upgrade().unwrap();
+impl<'a> DoubleEndedIterator for ColumnIterator<'a> {
+    fn next_back(&mut self) -> Option<NodeIndex> {
+        self.curr_up = self.matrix.up(self.curr_up);
 
-        if self.curr_down.borrow().get_row() != self.curr_up.borrow().get_row() {
-            Some(weak_next.clone())
+        if self.curr_down != self.curr_up {
+            Some(self.curr_up)
         }
         else {
             None
@@ -92,12 +88,23 @@ impl DoubleEndedIterator for ColumnIterator {
 }
 
 /// Iterate down through the elements of a column.
-pub fn iter_col(header: &OwnedNode) -> ColumnIterator {
-    ColumnIterator::new(header)
+pub fn iter_col(matrix: &Matrix, c: ColumnIndex) -> ColumnIterator<'_> {
+    ColumnIterator::new(matrix, c)
 }
 
 /// Return an iterator to iterate through the elements in the row
 /// occupied by `node`.
-pub fn iter_row(node: &WeakNode ) -> RowIterator {
-    RowIterator::new(&node)
+pub fn iter_row(matrix: &Matrix, node: NodeIndex) -> RowIterator<'_> {
+    RowIterator::new(matrix, node)
+}
+
+/// Return an iterator through every element in the row occupied by
+/// `node`, starting with `node` itself.
+pub fn iter_full_row(matrix: &Matrix, node: NodeIndex) -> impl DoubleEndedIterator<Item = NodeIndex> + '_ {
+    ::std::iter::once(node).chain(iter_row(matrix, node))
+}
+
+/// Iterate through the columns that may still be branched on.
+pub fn iter_columns(matrix: &Matrix) -> impl Iterator<Item = ColumnIndex> + '_ {
+    iter_row(matrix, ::node::ROOT).map(move |h| matrix.column(h))
 }
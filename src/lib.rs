@@ -1,10 +1,37 @@
+#[cfg(feature = "serde")]
+extern crate serde;
+
 mod node;
 mod problem;
 mod solver;
 mod cover;
 mod iter;
+mod parallel;
+mod component;
+mod rng;
+mod sample;
+mod limits;
+mod estimate;
+mod error;
+mod checkpoint;
+mod verify;
+mod explain;
+#[cfg(feature = "serde")]
+mod serialize;
 
 pub mod instances;
+pub mod chooser;
+pub mod formats;
 
 pub use problem::Problem;
-pub use solver::Solver;
+pub use error::Error;
+pub use checkpoint::Checkpoint;
+pub use verify::Verification;
+pub use explain::Infeasibility;
+pub use solver::{Solver, SolutionIterator};
+pub use parallel::ParallelSolutions;
+pub use chooser::ColumnChooser;
+pub use sample::Sampler;
+pub use limits::{SearchLimits, SearchResult, SearchStats, StopReason, Progress};
+pub use estimate::Estimate;
+pub use node::Color;
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// The clock and the cancellation flag are only looked at every this
+/// many nodes.
+const CHECK_INTERVAL: u64 = 64;
+
+/// `Progress::branches` covers at most this many levels of the search.
+const PROGRESS_LEVELS: usize = 16;
+
+/// `SearchLimits` bound the work a search may do before giving up.
+/// No limits are set by default.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::AtomicBool;
+/// use std::time::Duration;
+/// use exact_cover::SearchLimits;
+///
+/// let cancel = Arc::new(AtomicBool::new(false));
+/// let limits = SearchLimits::new()
+///     .max_nodes(1_000_000)
+///     .timeout(Duration::from_millis(100))
+///     .cancel_flag(cancel.clone());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    max_nodes: Option<u64>,
+    deadline: Option<Instant>,
+    cancel: Option<Arc<AtomicBool>>
+}
+
+impl SearchLimits {
+    pub fn new() -> SearchLimits {
+        SearchLimits::default()
+    }
+
+    /// Give up after visiting `n` nodes of the search tree.
+    pub fn max_nodes(mut self, n: u64) -> SearchLimits {
+        self.max_nodes = Some(n);
+        self
+    }
+
+    /// Give up once `deadline` has passed.
+    pub fn deadline(mut self, deadline: Instant) -> SearchLimits {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Give up once `timeout` has passed from now.
+    pub fn timeout(self, timeout: Duration) -> SearchLimits {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Give up once `flag` is set, e.g. from another thread.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> SearchLimits {
+        self.cancel = Some(flag);
+        self
+    }
+}
+
+/// The reason a search gave up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    NodeLimit,
+    Deadline,
+    Cancelled
+}
+
+/// The outcome of a search that may give up before it's done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchResult<T> {
+    Found(T),
+    /// The search finished without finding (another) solution.
+    NoSolution,
+    /// The search hit one of its limits; a solution may or may not
+    /// exist.
+    GaveUp(StopReason)
+}
+
+impl<T> SearchResult<T> {
+    /// Return the solution found, if any.
+    pub fn found(self) -> Option<T> {
+        match self {
+            SearchResult::Found(t) => Some(t),
+            _ => None
+        }
+    }
+
+    pub fn gave_up(&self) -> bool {
+        matches!(*self, SearchResult::GaveUp(_))
+    }
+}
+
+impl<T> From<Result<Option<T>, StopReason>> for SearchResult<T> {
+    fn from(r: Result<Option<T>, StopReason>) -> SearchResult<T> {
+        match r {
+            Ok(Some(t)) => SearchResult::Found(t),
+            Ok(None) => SearchResult::NoSolution,
+            Err(reason) => SearchResult::GaveUp(reason)
+        }
+    }
+}
+
+/// `SearchStats` describe the work done by a search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Number of nodes of the search tree visited, i.e. the number of
+    /// times an action was tried, or a bounded constraint was left
+    /// with no more actions.
+    pub nodes: u64,
+    /// Number of solutions reached. When a problem is split into
+    /// independent components, each component's solutions are
+    /// counted once, rather than each of their combinations.
+    pub solutions: u64,
+    /// Largest depth of the search tree reached, i.e. the largest
+    /// number of actions selected at once by the search (not counting
+    /// required actions), plus any bounded constraints left with no
+    /// more actions on the way.
+    pub max_depth: usize,
+    /// Number of link updates made while covering and uncovering.
+    pub updates: u64,
+    /// For each depth, the number of times the search branched `k`
+    /// ways, keyed by `k`.
+    pub branching: Vec<BTreeMap<usize, u64>>
+}
+
+impl SearchStats {
+    fn branch(&mut self, depth: usize, k: usize) {
+        if self.branching.len() <= depth {
+            self.branching.resize(depth + 1, BTreeMap::new());
+        }
+        *self.branching[depth].entry(k).or_insert(0) += 1;
+    }
+}
+
+/// `Progress` tells where a search is, for reporting on long runs
+/// (see `Solver::set_progress()`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// Number of nodes visited so far.
+    pub nodes: u64,
+    /// Number of actions currently selected by the search.
+    pub depth: usize,
+    /// For each of the top levels of the search, the index of the
+    /// branch being explored, and the number of branches.
+    pub branches: Vec<(usize, usize)>,
+    /// Estimated fraction of the search tree done, assuming every
+    /// branch is as large as its siblings.
+    pub fraction: f64
+}
+
+/// A progress observer, with the number of nodes between reports.
+#[derive(Clone)]
+pub(crate) struct Observer {
+    pub interval: u64,
+    pub report: Arc<dyn Fn(&Progress) + Send + Sync>
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Observer").field("interval", &self.interval).finish()
+    }
+}
+
+/// A `Budget` tracks the work done by a search, and checks it against
+/// the search limits.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Budget {
+    limits: SearchLimits,
+    nodes: u64,
+    pub stats: SearchStats,
+    pub progress: Option<Observer>,
+    /// The branch being explored and the number of branches, at each
+    /// level of the search; only kept up to date when observed.
+    path: Vec<(usize, usize)>
+}
+
+impl Budget {
+    pub fn new(limits: SearchLimits) -> Budget {
+        Budget { limits, nodes: 0, stats: SearchStats::default(), progress: None, path: Vec::new() }
+    }
+
+    /// Replace the limits, starting the node count over but keeping
+    /// the statistics.
+    pub fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+        self.nodes = 0;
+    }
+
+    /// Record a branch `k` ways, `depth` levels deep.
+    pub fn frame(&mut self, depth: usize, k: usize) {
+        self.stats.branch(depth, k);
+        if self.progress.is_some() {
+            self.path.truncate(depth);
+            self.path.push((0, k));
+        }
+    }
+
+    /// Record taking a branch, making `depth` levels in all.
+    pub fn node(&mut self, depth: usize) {
+        self.stats.nodes += 1;
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+        if let Some(ref progress) = self.progress {
+            // Count the branches tried so far at this level.
+            self.path.truncate(depth);
+            if let Some(last) = self.path.last_mut() {
+                last.0 += 1;
+            }
+            if self.stats.nodes.is_multiple_of(progress.interval) {
+                (progress.report)(&self.progress_report());
+            }
+        }
+    }
+
+    fn progress_report(&self) -> Progress {
+        let mut fraction = 0.0;
+        let mut scale = 1.0;
+        for &(i, k) in &self.path {
+            scale /= k as f64;
+            fraction += i.saturating_sub(1) as f64 * scale;
+        }
+        Progress { nodes: self.stats.nodes, depth: self.path.len(),
+                   branches: self.path.iter().take(PROGRESS_LEVELS).map(|&(i, k)| (i.saturating_sub(1), k)).collect(),
+                   fraction }
+    }
+
+    pub fn solution(&mut self) {
+        self.stats.solutions += 1;
+    }
+
+    /// Record a step of the search, or return why the search must
+    /// stop instead.
+    pub fn visit(&mut self) -> Result<(), StopReason> {
+        if self.limits.max_nodes.is_some_and(|n| self.nodes >= n) {
+            return Err(StopReason::NodeLimit);
+        }
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            if self.limits.cancel.as_ref().is_some_and(|f| f.load(Ordering::Relaxed)) {
+                return Err(StopReason::Cancelled);
+            }
+            if self.limits.deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(StopReason::Deadline);
+            }
+        }
+        self.nodes += 1;
+        Ok(())
+    }
+}
@@ -1,306 +1,322 @@
-use std::cell::{RefCell};
-use std::rc::{Rc, Weak};
-
+pub type NodeIndex = usize;
 pub type ColumnIndex = usize;
 pub type RowIndex = usize;
-
-/// `NodeExtra` contains the information specific to a node's
-/// type. For instance, only constraint (column) header nodes require
-/// a count of how many nodes are contained in the column. The type of
-/// node (header, inner, or root) can be directly inferred by the enum
-/// value.
-#[derive(Debug)]
-pub enum NodeExtra {
-    Row(RowIndex), // The node is an inner node, representing part of an action.
-    Count(usize),  // The node is a header for a constraints.
-    Root           // Root node.
+pub type Color = usize;
+
+/// Placeholder for links that don't apply to a node, e.g. the row of a
+/// header node.
+pub const NONE: usize = usize::MAX;
+
+/// The root node heads the list of column headers that may still be
+/// branched on.
+pub const ROOT: NodeIndex = 0;
+
+/// `Bounds` records how many actions may satisfy a constraint, and
+/// how many currently do. A plain (primary) constraint must be
+/// satisfied exactly once, i.e. `lo == hi == 1`; a secondary
+/// constraint has `lo == 0`, and is never branched on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub lo: usize,
+    pub hi: usize,
+    pub used: usize
 }
 
+impl Bounds {
+    pub fn new(lo: usize, hi: usize) -> Bounds {
+        Bounds { lo, hi, used: 0 }
+    }
+
+    /// Return the number of additional actions required to satisfy
+    /// the constraint.
+    pub fn need(&self) -> usize {
+        self.lo.saturating_sub(self.used)
+    }
+
+    /// Return the number of additional actions that may still
+    /// satisfy the constraint.
+    pub fn remaining(&self) -> usize {
+        self.hi - self.used
+    }
 
-/// A `Node` represents multiple types in the DLX algorithm. Most nodes
-/// are 'inner' nodes, essentially representing 1's in the sparse
-/// matrix representation of the problem. Headers `Node`s contain a
-/// current count of how many actions can be used to satisfy the
-/// constaint.
+    /// Return true iff no additional action may satisfy the
+    /// constraint.
+    pub fn is_full(&self) -> bool {
+        self.used == self.hi
+    }
+
+    /// Return true iff the column is branched on while it isn't full:
+    /// it's primary.
+    pub fn is_primary(&self) -> bool {
+        self.lo > 0
+    }
+}
+
+/// A `Matrix` is the sparse 0-1 matrix of an exact cover problem, laid
+/// out as in Knuth's DLX: every node (the root, the column headers,
+/// and the 1-entries of the matrix) is an index into a set of parallel
+/// vectors holding its links and its column. Nodes are never removed
+/// from the vectors; covering only rewires the links, so uncovering
+/// in reverse order restores the matrix exactly.
 ///
-/// Throughout the code, nodes are almost never dealt with
-/// directly. Instead, nodes are handled either through `WeakNode`s or
-/// `OwnedNode`s.
+/// Columns and rows also have their own indices. Column `c` is headed
+/// by node `headers[c]`, and the per-column vectors (`len`, `bounds`
+/// and `purity`) are indexed by column rather than by node. Row `r`
+/// starts at node `rows[r]`.
 ///
-/// `Node`s exclusively store neighbor pointers as `WeakNode`s to
-/// prevent memory leaks via cyclical references.
-#[derive(Debug)]
-pub struct Node {
-    up: Weak<RefCell<Node>>,
-    down: Weak<RefCell<Node>>,
-    left: Weak<RefCell<Node>>,
-    right: Weak<RefCell<Node>>,
-    at_self: Weak<RefCell<Node>>,
-
-    pub column: Option<ColumnIndex>,
-    header: Weak<RefCell<Node>>,
-    extra: NodeExtra
+/// The left and right links of the header nodes form the list of
+/// columns that may still be branched on, starting at `ROOT`: the
+/// primary columns that aren't full, and haven't been given up on by
+/// the search (see `FrameState`). Other columns (e.g. secondary
+/// columns) link to themselves.
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    up: Vec<NodeIndex>,
+    down: Vec<NodeIndex>,
+    left: Vec<NodeIndex>,
+    right: Vec<NodeIndex>,
+    column: Vec<ColumnIndex>,
+    row: Vec<RowIndex>,
+    color: Vec<Option<Color>>,
+
+    headers: Vec<NodeIndex>,
+    len: Vec<usize>,
+    bounds: Vec<Bounds>,
+    purity: Vec<Option<(Color, usize)>>,
+
+    rows: Vec<NodeIndex>,
+
+    updates: u64
 }
 
-
-pub type OwnedNode = Rc<RefCell<Node>>;
-pub type WeakNode = Weak<RefCell<Node>>;
-
-impl Node {
-    pub fn new_header(col: Option<usize>) -> OwnedNode {
-        Self::new(col, None, NodeExtra::Count(0))
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    pub fn new_inner(header: &OwnedNode, row: usize) -> OwnedNode {
-        Self::new(header.borrow().column, Some(&Rc::downgrade(&header)), NodeExtra::Row(row))
+impl Matrix {
+    /// Create an empty matrix, containing only the root node.
+    pub fn new() -> Matrix {
+        let mut m = Matrix { up: Vec::new(), down: Vec::new(), left: Vec::new(), right: Vec::new(),
+                             column: Vec::new(), row: Vec::new(), color: Vec::new(),
+                             headers: Vec::new(), len: Vec::new(), bounds: Vec::new(), purity: Vec::new(),
+                             rows: Vec::new(), updates: 0 };
+        m.new_node(NONE, NONE, None);
+        m
     }
 
-    pub fn new_root() -> OwnedNode {
-        Self::new(None, None, NodeExtra::Root)
+    /// Create new node that circularly points to itself.
+    fn new_node(&mut self, col: ColumnIndex, row: RowIndex, color: Option<Color>) -> NodeIndex {
+        let n = self.up.len();
+        self.up.push(n);
+        self.down.push(n);
+        self.left.push(n);
+        self.right.push(n);
+        self.column.push(col);
+        self.row.push(row);
+        self.color.push(color);
+        n
     }
 
-    /// Create new node that circularly points to itself. 
-    fn new(col: Option<usize>, header: Option<&WeakNode>, e: NodeExtra) -> OwnedNode {
-	let rc = Rc::new(RefCell::new(Node {
-	    up: Weak::new(), down: Weak::new(), 
-            left: Weak::new(), right: Weak::new(),
-            at_self: Weak::new(),
-            header: Weak::new(),
-            column: col, extra: e
-	}));
+    /// Add a new column with the given bounds, returning its index.
+    /// Only primary columns that aren't full are linked into the list
+    /// scanned by `choose_column()`.
+    pub fn add_column(&mut self, bounds: Bounds) -> ColumnIndex {
+        let c = self.headers.len();
+        let h = self.new_node(c, NONE, None);
+        self.headers.push(h);
+        self.len.push(0);
+        self.bounds.push(bounds);
+        self.purity.push(None);
+
+        if bounds.is_primary() && !bounds.is_full() {
+            self.prepend_left(ROOT, h);
+        }
+        c
+    }
 
-        {
-            let mut nc = (*rc).borrow_mut();
-            let w = Rc::downgrade(&rc);
-	    nc.up = w.clone();
-	    nc.down = w.clone();
-	    nc.left = w.clone();
-	    nc.right = w.clone();
-            nc.at_self = w.clone();
-            nc.header = match header {
-                Some(n) => n.clone(),
-                None => w.clone()
+    /// Add a new row with a node in each of the given columns,
+    /// returning its index.
+    pub fn add_row(&mut self, entries: &[(ColumnIndex, Option<Color>)]) -> RowIndex {
+        let r = self.rows.len();
+        let first = self.up.len();
+        for &(c, color) in entries {
+            let n = self.new_node(c, r, color);
+            let h = self.headers[c];
+            self.prepend_up(h, n);
+            self.len[c] += 1;
+            if n != first {
+                self.prepend_left(first, n);
             }
         }
-	rc
+        self.rows.push(first);
+        r
+    }
+
+    /// Prepend `node` to the left of `root`.
+    fn prepend_left(&mut self, root: NodeIndex, node: NodeIndex) {
+        let l = self.left[root];
+        self.right[node] = root;
+        self.left[node] = l;
+        self.right[l] = node;
+        self.left[root] = node;
+    }
+
+    /// Prepend `node` above `root`.
+    fn prepend_up(&mut self, root: NodeIndex, node: NodeIndex) {
+        let u = self.up[root];
+        self.down[node] = root;
+        self.up[node] = u;
+        self.down[u] = node;
+        self.up[root] = node;
     }
 
     /// return the down link
-    pub fn down(&self) -> WeakNode {
-        self.down.clone()
+    pub fn down(&self, n: NodeIndex) -> NodeIndex {
+        self.down[n]
     }
 
     /// return the up link
-    pub fn up(&self) -> WeakNode {
-        self.up.clone()
+    pub fn up(&self, n: NodeIndex) -> NodeIndex {
+        self.up[n]
     }
 
     /// return the left link
-    pub fn left(&self) -> WeakNode {
-        self.left.clone()
+    pub fn left(&self, n: NodeIndex) -> NodeIndex {
+        self.left[n]
     }
 
     /// return the right link
-    pub fn right(&self) -> WeakNode {
-        self.right.clone()
+    pub fn right(&self, n: NodeIndex) -> NodeIndex {
+        self.right[n]
     }
 
-    /// Return the count for a header node, and None for other nodes.
-    pub fn get_count(&self) -> Option<usize> {
-        match self.extra {
-            NodeExtra::Count(i) => Some(i),
-            _ => None
-        }
+    /// Return the column of a header or inner node.
+    pub fn column(&self, n: NodeIndex) -> ColumnIndex {
+        self.column[n]
     }
 
-    pub fn dec_count(&mut self) {
-        let c = match self.extra {
-            NodeExtra::Count(i) => i,
-            _ => return
-        };
-        self.extra = NodeExtra::Count(c-1);
+    /// Return the row of an inner node, and `NONE` for other nodes.
+    pub fn row(&self, n: NodeIndex) -> RowIndex {
+        self.row[n]
     }
 
-    pub fn inc_count(&mut self) {
-        let c = match self.extra {
-            NodeExtra::Count(i) => i,
-            _ => return
-        };
-        self.extra = NodeExtra::Count(c+1);
+    /// Return the color of an inner node, and None for uncolored
+    /// inner nodes and other nodes.
+    pub fn color(&self, n: NodeIndex) -> Option<Color> {
+        self.color[n]
     }
 
-    /// Return the associated row index for an inner node, and None
-    /// for other nodes.
-    pub fn get_row(&self) -> Option<usize> {
-        match self.extra {
-            NodeExtra::Row(i) => Some(i),
-            _ => None
-        }
+    /// Return the header node of a column.
+    pub fn header(&self, c: ColumnIndex) -> NodeIndex {
+        self.headers[c]
     }
 
-    /// Return the constraint header of this node.
-    pub fn get_header(&self) -> WeakNode {
-        self.header.clone()
+    /// Return the first node of a row.
+    pub fn row_start(&self, r: RowIndex) -> NodeIndex {
+        self.rows[r]
     }
 
-    /// Return true iff this is a header node and the column has
-    /// already been chosen.
-    pub fn is_already_chosen(&self) -> bool {
-        if let NodeExtra::Count(_) = self.extra {
-            let r = self.right.upgrade().unwrap();
-            let l = r.borrow().left().upgrade().unwrap();
-            let lc = l.borrow().column;
-            lc != self.column
-        } else {
-            false
-        }
+    pub fn num_columns(&self) -> usize {
+        self.headers.len()
     }
 
-    /// Remove a node from its column
-    pub fn remove_from_column(&mut self) {
-	let up_weak = self.up.clone();
-	let down_weak = self.down.clone();
-	let up = up_weak.upgrade().unwrap();
-	up.borrow_mut().down = down_weak.clone();
-	let down = down_weak.upgrade().unwrap();
-	down.borrow_mut().up = up_weak;
+    /// Return the number of rows left in a column.
+    pub fn len(&self, c: ColumnIndex) -> usize {
+        self.len[c]
     }
 
-    /// Remove a node from its row
-    pub fn remove_from_row(&mut self) {
-	let l = self.left.clone();
-	let r = self.right.clone();
-	let lrc = self.left.upgrade().unwrap();
-	(*lrc).borrow_mut().right = r;
-	let rrc = self.right.upgrade().unwrap();
-	(*rrc).borrow_mut().left = l;
+    pub fn bounds(&self, c: ColumnIndex) -> Bounds {
+        self.bounds[c]
     }
 
-    /// Re-add a node to its column
-    pub fn reinsert_into_column(&mut self) {
-	let urc = self.up.upgrade().unwrap();
-	(*urc).borrow_mut().down = self.at_self.clone();
-	let drc = self.down.upgrade().unwrap();
-	(*drc).borrow_mut().up = self.at_self.clone();
+    /// Return the color a column has been purified with, along with
+    /// the number of selected rows sharing it.
+    pub fn purity(&self, c: ColumnIndex) -> Option<(Color, usize)> {
+        self.purity[c]
     }
 
-    /// Re-add a node to its row
-    pub fn reinsert_into_row(&mut self) {
-	let lrc = self.left.upgrade().unwrap();
-	(*lrc).borrow_mut().right = self.at_self.clone();
-	let rrc = self.right.upgrade().unwrap();
-	(*rrc).borrow_mut().left = self.at_self.clone();
+    pub fn set_purity(&mut self, c: ColumnIndex, purity: Option<(Color, usize)>) {
+        self.purity[c] = purity;
     }
-}
-pub fn get_header(n: &WeakNode) -> WeakNode {
-    let s = n.upgrade().unwrap();
-    let h = s.borrow().get_header();
-    h.clone()
-}
-
-// impl fmt::Display for Node {
-//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-//         write!(f, "({:?}, {:?})", self.row, self.column)
-//     }
-// }
 
-/// Prepend `node` to the left of `root.
-pub fn prepend_left(root: &OwnedNode, node: &WeakNode) {
-    let l = (*node).upgrade().unwrap();
-
-    {
-	let mut n = l.borrow_mut();
-	n.right = Rc::downgrade(&root);
-	n.left = root.borrow_mut().left.clone();
-    }
-    {
-	let mut head = root.borrow_mut();
-	head.left = (*node).clone();
-    }
-    {
-	let pleft = l.borrow_mut();
-	let prev_left  = pleft.left.upgrade().unwrap();
-	prev_left.borrow_mut().right = (*node).clone();
+    pub fn inc_used(&mut self, c: ColumnIndex) {
+        self.bounds[c].used += 1;
     }
 
-}
-
-/// Prepend node above root.
-pub fn prepend_up(root: &OwnedNode, node: &WeakNode) {
-    let u = (*node).upgrade().unwrap();
-
-    {
-	let mut n = u.borrow_mut();
-	n.down = Rc::downgrade(&root);
-	n.up = root.borrow_mut().up.clone();
+    pub fn dec_used(&mut self, c: ColumnIndex) {
+        self.bounds[c].used -= 1;
     }
-    {
-	let mut head = root.borrow_mut();
-	head.up = (*node).clone();
+
+    /// Return the branching degree of a column: the number of ways an
+    /// action can be picked to satisfy the constraint, plus one for
+    /// picking none once it's satisfied often enough, or 0 if there
+    /// are too few actions left to satisfy it.
+    pub fn degree(&self, c: ColumnIndex) -> usize {
+        (self.len[c] + 1).saturating_sub(self.bounds[c].need())
     }
-    {
-	let pup = u.borrow_mut();
-	let prev_up  = pup.up.upgrade().unwrap();
-	prev_up.borrow_mut().down = (*node).clone();
+
+    /// Return the number of branches a search takes on a column: one
+    /// per row, plus one for taking no more rows if the column is
+    /// satisfied often enough already.
+    pub fn branches(&self, c: ColumnIndex) -> usize {
+        self.len[c] + (self.bounds[c].need() == 0) as usize
     }
-}
 
-#[derive(Debug)]
-pub struct Row<Action: Copy> {
-    nodes: Vec<Rc<RefCell<Node>>>,
-    id: usize,
-    action: Action
-}
+    /// Return true iff the column can't be satisfied by any more
+    /// actions.
+    pub fn is_already_chosen(&self, c: ColumnIndex) -> bool {
+        self.bounds[c].is_full()
+    }
 
-impl<Action: Copy> Row<Action> {
-    pub fn new(nodes: Vec<OwnedNode>, action: Action, index: usize) -> Self {
-        let l = nodes.len();
-        for i in l..(2*l) {
-            let mut n = nodes[i % l].borrow_mut();
-            n.left = Rc::downgrade(&nodes[(i-1) % l]);
-            n.right = Rc::downgrade(&nodes[(i+1) % l]);
+    /// Return true iff the column can't be satisfied by any more
+    /// actions of the given color.
+    pub fn rejects_color(&self, c: ColumnIndex, color: Option<Color>) -> bool {
+        match (self.purity[c], color) {
+            (Some((p, _)), Some(color)) => p != color,
+            (Some(_), None) => true,
+            (None, _) => self.is_already_chosen(c)
         }
-        Row {nodes: nodes, id: index, action: action }
     }
 
-    pub fn first_node(&self) -> WeakNode {
-        Rc::downgrade(&self.nodes[0])
+    /// Return the number of times a node has been removed from or
+    /// reinserted into its column or row.
+    pub fn updates(&self) -> u64 {
+        self.updates
     }
 
-    pub fn iter(&self) -> FullRowIterator {
-        FullRowIterator::new(&self)
-    }
-    pub fn action(&self) -> Action {
-        self.action
+    /// Remove a node from its column, updating the column's count.
+    pub fn remove_from_column(&mut self, n: NodeIndex) {
+        self.updates += 1;
+        let (u, d) = (self.up[n], self.down[n]);
+        self.down[u] = d;
+        self.up[d] = u;
+        self.len[self.column[n]] -= 1;
     }
-}
-
-/// Iterator for row nodes (not header rwos)
-pub struct FullRowIterator {
-    head_column: ColumnIndex,
-    curr: OwnedNode,
-    started: bool
-}
 
-impl FullRowIterator {
-    pub fn new<A: Copy>(row: &Row<A>) -> FullRowIterator {
-        FullRowIterator{ head_column: row.nodes[0].borrow().column.unwrap(), curr: row.nodes[0].clone(),
-                         started: false }
+    /// Re-add a node to its column, updating the column's count.
+    pub fn reinsert_into_column(&mut self, n: NodeIndex) {
+        self.updates += 1;
+        let (u, d) = (self.up[n], self.down[n]);
+        self.down[u] = n;
+        self.up[d] = n;
+        self.len[self.column[n]] += 1;
     }
-}
 
-impl Iterator for FullRowIterator {
-    type Item = WeakNode;
+    /// Remove a node from its row
+    pub fn remove_from_row(&mut self, n: NodeIndex) {
+        self.updates += 1;
+        let (l, r) = (self.left[n], self.right[n]);
+        self.right[l] = r;
+        self.left[r] = l;
+    }
 
-    fn next(&mut self) -> Option<WeakNode> {
-        let ret = self.curr.clone();
-        if self.started {
-            if ret.borrow().column.unwrap() == self.head_column {
-                return None
-            }
-        }
-        self.curr = { let r = self.curr.borrow().right(); r.upgrade().unwrap() };
-        self.started = true;
-        Some(Rc::downgrade(&ret))
+    /// Re-add a node to its row
+    pub fn reinsert_into_row(&mut self, n: NodeIndex) {
+        self.updates += 1;
+        let (l, r) = (self.left[n], self.right[n]);
+        self.right[l] = n;
+        self.left[r] = n;
     }
 }
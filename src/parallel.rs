@@ -0,0 +1,219 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::thread::{self, JoinHandle};
+
+use node::{Matrix, ColumnIndex, NodeIndex};
+use iter::iter_col;
+use problem::{Problem, Constraint, Action};
+use solver::{Solver, Search, FrameState};
+use chooser::{ColumnChooser, choose_column};
+use limits::{SearchLimits, Budget};
+
+/// The search is split until there are at least this many subtrees
+/// per thread, so that threads finishing early can pick up more work.
+const TASKS_PER_THREAD: usize = 8;
+
+/// The search is never split deeper than this.
+const MAX_SPLIT_DEPTH: usize = 6;
+
+/// Number of solutions that may be buffered before the threads wait
+/// for the consumer.
+const SOLUTIONS_PER_THREAD: usize = 64;
+
+/// A subtree of the search, identified by the branches taken on the
+/// path from the root: the column branched on, and the row selected
+/// in it, or None if no more rows were.
+type Prefix = Vec<(ColumnIndex, Option<NodeIndex>)>;
+
+/// Take the branches of `prefix` in turn, as the sequential search
+/// did on its way down.
+///
+/// The frames are dropped without being finished, so the matrix is
+/// left in the state of the subtree, including the siblings tried
+/// before each branch.
+fn replay(m: &mut Matrix, prefix: &[(ColumnIndex, Option<NodeIndex>)]) {
+    for &(c, branch) in prefix {
+        let mut frame = FrameState::new(m, c);
+        while frame.advance(m).expect("prefix branch not in chosen column") != branch {}
+    }
+}
+
+/// Split the search below the matrix into roughly `tasks` subtrees.
+/// Subtrees which are already solutions are kept as they are; dead
+/// ends are dropped.
+fn split<C, H: ColumnChooser<C>>(m: &Matrix, names: &[C], chooser: &mut H, tasks: usize) -> Vec<Prefix> {
+    let mut prefixes = vec![Vec::new()];
+
+    for _ in 0..MAX_SPLIT_DEPTH {
+        if prefixes.len() >= tasks {
+            break;
+        }
+
+        let mut next = Vec::new();
+        let mut grew = false;
+        for prefix in prefixes {
+            let mut m = m.clone();
+            replay(&mut m, &prefix);
+            match choose_column(&m, names, chooser) {
+                None => next.push(prefix),
+                Some(c) => {
+                    if m.degree(c) > 0 {
+                        grew = true;
+                        let skip = if m.bounds(c).need() == 0 { Some(None) } else { None };
+                        for branch in iter_col(&m, c).map(Some).chain(skip) {
+                            let mut p = prefix.clone();
+                            p.push((c, branch));
+                            next.push(p);
+                        }
+                    }
+                }
+            }
+        }
+
+        prefixes = next;
+        if !grew {
+            break;
+        }
+    }
+    prefixes
+}
+
+/// The subtrees of a search, shared by the threads working on it.
+struct Tasks {
+    prefixes: Vec<Prefix>,
+    next: AtomicUsize
+}
+
+impl Tasks {
+    /// Take the next unclaimed subtree, returning a copy of the
+    /// problem set up to search it, along with the actions selected
+    /// so far.
+    fn take<A: Action, C: Constraint, H>(&self, solver: &Solver<A, C, H>) -> Option<(Problem<A, C>, Vec<A>)> {
+        let prefix = self.prefixes.get(self.next.fetch_add(1, Ordering::Relaxed))?;
+
+        let mut problem = solver.problem.clone();
+        replay(&mut problem.matrix, prefix);
+
+        let mut partial = solver.partial_solution.clone();
+        partial.extend(prefix.iter().filter_map(|b| b.1).map(|n| problem.get_action(problem.matrix.row(n))));
+        Some((problem, partial))
+    }
+}
+
+fn num_threads(threads: usize) -> usize {
+    if threads > 0 {
+        threads
+    } else {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+}
+
+/// An iterator over the solutions found by `Solver::par_solutions()`,
+/// in no particular order.
+///
+/// Dropping the iterator stops the search and waits for the threads
+/// to finish.
+pub struct ParallelSolutions<A> {
+    receiver: Option<Receiver<Vec<A>>>,
+    cancel: Arc<AtomicBool>,
+    workers: Vec<JoinHandle<()>>
+}
+
+impl<A> Iterator for ParallelSolutions<A> {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Vec<A>> {
+        self.receiver.as_ref().and_then(|r| r.recv().ok())
+    }
+}
+
+impl<A> Drop for ParallelSolutions<A> {
+    fn drop(&mut self) {
+        // The threads check the flag as they search; closing the
+        // channel also wakes those waiting to send a solution.
+        self.cancel.store(true, Ordering::Relaxed);
+        self.receiver.take();
+        for w in self.workers.drain(..) {
+            let _ = w.join();
+        }
+    }
+}
+
+impl<A, C, H> Solver<A, C, H>
+    where A: Action + Send + 'static, C: Constraint + Send + 'static,
+          H: ColumnChooser<C> + Clone + Send + 'static {
+    fn tasks(&self, threads: usize) -> Arc<Tasks> {
+        let prefixes = split(&self.problem.matrix, &self.problem.constraints,
+                             &mut self.chooser.clone(), threads * TASKS_PER_THREAD);
+        Arc::new(Tasks { prefixes, next: AtomicUsize::new(0) })
+    }
+
+    /// Enumerate every solution on `threads` threads (or one per CPU
+    /// if `threads` is 0).
+    ///
+    /// The first few levels of the search tree are expanded up front,
+    /// and the resulting subtrees are searched independently, each on
+    /// its own copy of the problem. Solutions are returned as soon as
+    /// they are found, so their order varies from run to run.
+    pub fn par_solutions(&self, threads: usize) -> ParallelSolutions<A> {
+        let threads = num_threads(threads);
+        let tasks = self.tasks(threads);
+        let (sender, receiver) = sync_channel(threads * SOLUTIONS_PER_THREAD);
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let workers = (0..threads).map(|_| {
+            let solver = self.clone();
+            let tasks = tasks.clone();
+            let sender = sender.clone();
+            let mut budget = Budget::new(SearchLimits::new().cancel_flag(cancel.clone()));
+            thread::spawn(move || {
+                while let Some((problem, partial)) = tasks.take(&solver) {
+                    let mut search = Search::new(problem, solver.chooser.clone(), solver.shuffle.clone(), partial);
+                    loop {
+                        match search.next_within(&mut budget) {
+                            Ok(Some(sol)) => {
+                                if sender.send(sol).is_err() {
+                                    return;
+                                }
+                            },
+                            Ok(None) => break,
+                            Err(_) => return
+                        }
+                    }
+                }
+            })
+        }).collect();
+
+        ParallelSolutions { receiver: Some(receiver), cancel, workers }
+    }
+
+    /// Count the solutions on `threads` threads (or one per CPU if
+    /// `threads` is 0), splitting the search as `par_solutions()`
+    /// does. Each subtree is counted as `count_solutions()` would
+    /// count it, splitting it into components if `set_decompose()`
+    /// was set.
+    pub fn par_count_solutions(&self, threads: usize) -> usize {
+        let threads = num_threads(threads);
+        let tasks = self.tasks(threads);
+
+        let workers: Vec<JoinHandle<usize>> = (0..threads).map(|_| {
+            let solver = self.clone();
+            let tasks = tasks.clone();
+            thread::spawn(move || {
+                let mut count = 0;
+                while let Some((mut problem, _)) = tasks.take(&solver) {
+                    let mut counter = solver.counter();
+                    count += if solver.decompose {
+                        counter.count_components(&mut problem.matrix, usize::MAX)
+                    } else {
+                        counter.count(&mut problem.matrix, usize::MAX)
+                    };
+                }
+                count
+            })
+        }).collect();
+
+        workers.into_iter().map(|w| w.join().unwrap()).sum()
+    }
+}
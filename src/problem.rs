@@ -1,10 +1,10 @@
-use node::{WeakNode, OwnedNode, Node, Row};
-use iter::{iter_row};
-use node::{prepend_left, prepend_up};
+use node::{Matrix, Bounds, Color, ColumnIndex, RowIndex};
+use iter::{iter_col, iter_full_row, iter_columns};
 use std::hash::Hash;
-use std::collections::{HashMap};
-use std::rc::{Rc};
-use cover::{cover_column};
+use std::collections::{HashMap, HashSet};
+use cover::{select_row, unselect_row};
+use component::components;
+use error::Error;
 
 pub trait Constraint : Clone + Hash + Eq {}
 impl<T: Clone + Hash + Eq> Constraint for T {}
@@ -23,24 +23,52 @@ impl<T: Copy + Hash + Eq> Action for T {}
 /// explicitly add constraints via the `add_constraint()` function,
 /// but this is typically unnecesssary..
 ///
+/// A problem may also have secondary constraints, added via
+/// `add_secondary_constraint()`. A secondary constraint may be
+/// satisfied by at most one action in a solution, but unlike a
+/// primary constraint, it does not have to be satisfied at all. More
+/// generally, `add_bounded_constraint()` adds a constraint that must
+/// be satisfied by between `lo` and `hi` actions.
+///
+/// The solver only branches on primary constraints (those with
+/// `lo > 0`), so an action touching none of them is never part of a
+/// solution unless it is required (see `Solver::require_action()`).
+///
 /// Internally, a problem is represented as a sparse 0-1 matrix, where
 /// each row represents an action and each column represents a
 /// constraint. To facility solving via Algorithm X, the 1-entries of
-/// the matrix are stored `Node`s, with each entry linked to previous
-/// and next column entries (named 'up' and 'down', resp.) and row
-/// entries (name 'left' and 'right', respectively).
+/// the matrix are stored as nodes of a `Matrix`, with each entry
+/// linked to previous and next column entries (named 'up' and 'down',
+/// resp.) and row entries (name 'left' and 'right', respectively).
+#[derive(Clone)]
 pub struct Problem<A: Action, C: Constraint> {
-    root: OwnedNode,
-    constraints: Vec<OwnedNode>,
+    pub(crate) matrix: Matrix,
     actions: Vec<Row<A>>,
+    pub(crate) constraints: Vec<C>,
     constraint_map: HashMap<C, usize>,
     action_map: HashMap<A, usize>
 }
 
+/// A `Row` holds the action (and its cost) of the matrix row with the
+/// same index.
+#[derive(Debug, Clone)]
+struct Row<A: Action> {
+    action: A,
+    cost: u64,
+    /// Whether the row has been selected by `require_row()`.
+    required: bool
+}
+
+impl<A: Action, C: Constraint> Default for Problem<A, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<A: Action, C: Constraint> Problem<A, C> {
     pub fn new() -> Problem<A, C> {
-        Problem { constraints: Vec::new(), actions: Vec::new(),
-                  root: Node::new_root(),
+        Problem { matrix: Matrix::new(), actions: Vec::new(),
+                  constraints: Vec::new(),
                   constraint_map: HashMap::new(),
                   action_map: HashMap::new()
         }
@@ -48,111 +76,256 @@ impl<A: Action, C: Constraint> Problem<A, C> {
 
     /// Add a constraint if it doesn't already exist.
     pub fn add_constraint(&mut self, constraint: &C) {
-        let curr_size = self.constraint_map.len();
+        self.add_bounded_constraint(constraint, 1, 1);
+    }
+
+    /// Add a secondary constraint if it doesn't already exist.
+    ///
+    /// Secondary constraints must be added before any action that
+    /// refers to them; otherwise, `add_action()` will have already
+    /// created them as primary constraints.
+    pub fn add_secondary_constraint(&mut self, constraint: &C) {
+        self.add_bounded_constraint(constraint, 0, 1);
+    }
+
+    /// Add a constraint that must be satisfied by at least `lo` and
+    /// at most `hi` actions, if it doesn't already exist. A constraint
+    /// with `lo == 0` is secondary.
+    ///
+    /// Like secondary constraints, bounded constraints must be added
+    /// before any action that refers to them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hi` is 0 or smaller than `lo`.
+    pub fn add_bounded_constraint(&mut self, constraint: &C, lo: usize, hi: usize) {
+        assert!(hi > 0 && lo <= hi, "invalid constraint bounds [{}, {}]", lo, hi);
+
         if !self.constraint_map.contains_key(constraint) {
-            let c = Node::new_header(Some(curr_size));
-            prepend_left(&mut self.root, &Rc::downgrade(&c));
-            self.constraints.push(c);
-            self.constraint_map.insert(constraint.clone(), curr_size);
+            let c = self.matrix.add_column(Bounds::new(lo, hi));
+            self.constraints.push(constraint.clone());
+            self.constraint_map.insert(constraint.clone(), c);
         }
     }
 
     /// Add a new action, creating additional constraints on
-    /// demand. Actions must not already have already existed.
-    pub fn add_action(&mut self, a: A, clist: &[C]) {
-        // Ignore actions that don't satisfy constraints
-        if clist.is_empty() {
-            return
-        }
+    /// demand. The action must not already exist, and must satisfy
+    /// at least one constraint; otherwise, the problem is left as it
+    /// was and an error is returned.
+    pub fn add_action(&mut self, a: A, clist: &[C]) -> Result<(), Error<A, C>> {
+        self.add_colored_action(a, clist, &[])
+    }
 
-        // Ignore actions that are already present.
+    /// Add a new action that satisfies the constraints in `clist`,
+    /// and that touches each secondary constraint in `colored` with
+    /// the given color. Any number of actions in a solution may touch
+    /// a colored constraint, as long as they all agree on its color.
+    ///
+    /// Colored constraints that haven't been previously defined are
+    /// added as secondary constraints. A colored constraint must not be
+    /// primary, nor be in `clist`; otherwise, the problem is left as it
+    /// was and an error is returned.
+    pub fn add_colored_action(&mut self, a: A, clist: &[C], colored: &[(C, Color)]) -> Result<(), Error<A, C>> {
+        if clist.is_empty() && colored.is_empty() {
+            return Err(Error::EmptyAction(a));
+        }
         if self.action_map.contains_key(&a) {
-            return
+            return Err(Error::DuplicateAction(a));
+        }
+        for (x, _) in colored {
+            if clist.contains(x) || self.constraint_bounds(x).is_some_and(|(lo, _)| lo > 0) {
+                return Err(Error::ColoredPrimary { action: a, constraint: x.clone() });
+            }
         }
 
         // extend the constraint list to accomodate all constraints, if necessary
         for x in clist {
             self.add_constraint(x);
         }
+        for (x, _) in colored {
+            self.add_secondary_constraint(x);
+        }
 
-        // create a row from those nodes
-        let new_id  = self.actions.len();
-
-        // Create and collect new nodes for each constraint.
-        let nodes = clist.iter().map(|x| {
-            let ci = *self.constraint_map.get(x).unwrap();
-            let c = &self.constraints[ci];
-            let n = Node::new_inner(&c, new_id);
-            prepend_up(c, &Rc::downgrade(&n));
-            c.borrow_mut().inc_count();
-            n
-        }).collect();
+        // Create a row with a node for each constraint.
+        let entries: Vec<(ColumnIndex, Option<Color>)> = clist.iter().map(|x| (x, None))
+            .chain(colored.iter().map(|&(ref x, color)| (x, Some(color))))
+            .map(|(x, color)| (self.constraint_map[x], color))
+            .collect();
+        let new_id = self.matrix.add_row(&entries);
 
-        self.actions.push(Row::new(nodes, a, new_id));
+        self.actions.push(Row { action: a, cost: 0, required: false });
         self.action_map.insert(a, new_id);
-    }
-
-    /// Choose the column with the smallest count
-    pub fn choose_column(&self) -> Option<&OwnedNode> {
-        iter_row(&Rc::downgrade(&self.root))
-            .map( |ref node| self.get_column(&node) )
-            .min_by_key( |ref c| c.borrow().get_count() )
+        Ok(())
     }
 
     /// Return the number of constraints currently in the problem.
     pub fn num_constraints(&self) -> usize {
-        self.constraints.len()
+        self.matrix.num_columns()
+    }
+
+    /// Return the number of actions currently in the problem.
+    pub fn num_actions(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Return the number of secondary constraints currently in the
+    /// problem.
+    pub fn num_secondary_constraints(&self) -> usize {
+        (0..self.matrix.num_columns())
+            .filter(|c| self.matrix.bounds(*c).lo == 0)
+            .count()
     }
 
-    /// Return the column associated with a node.
-    fn get_column(&self, row_node: &WeakNode) -> &OwnedNode {
-        let s = row_node.upgrade().unwrap();
-        let ci = s.borrow().column.unwrap();
-        &self.constraints[ci]
+    /// Return true if `constraint` exists and is a secondary
+    /// constraint.
+    pub fn is_secondary(&self, constraint: &C) -> bool {
+        self.constraint_bounds(constraint).is_some_and(|(lo, _)| lo == 0)
     }
 
-    /// Return the row associated with a node.
-    fn get_row(&self, row_node: &WeakNode) -> &Row<A> {
-        let s = row_node.upgrade().unwrap();
-        let ri = s.borrow().get_row().unwrap();
-        &self.actions[ri]
+    /// Return the `(lo, hi)` bounds on the number of actions that may
+    /// satisfy `constraint`, if it exists.
+    pub fn constraint_bounds(&self, constraint: &C) -> Option<(usize, usize)> {
+        self.constraint_map.get(constraint).map(|ci| {
+            let b = self.matrix.bounds(*ci);
+            (b.lo, b.hi)
+        })
     }
 
-    /// Return the action associated with a node.
-    pub fn get_action(&self, row_node: &WeakNode) -> A {
-        self.get_row(row_node).action()
+    /// Split what remains of the problem (after any required rows)
+    /// into independent subproblems, which share no actions or
+    /// constraints. Every solution of the problem combines one
+    /// solution of each subproblem.
+    ///
+    /// Only primary constraints that could still take more actions,
+    /// and the actions that could satisfy them, end up in a
+    /// subproblem. If there are none, there are no subproblems.
+    pub fn components(&self) -> Vec<Problem<A, C>> {
+        self.split(components(&self.matrix))
     }
 
-    /// Return true if it is possible to require a row
+    /// Build a subproblem for each group of columns returned by
+    /// `component::components()`.
+    pub(crate) fn split(&self, comps: Vec<Vec<ColumnIndex>>) -> Vec<Problem<A, C>> {
+        let m = &self.matrix;
+        comps.into_iter().map(|cols| {
+            let mut sub = Problem::new();
+            let mut columns = HashMap::new();
+            let mut rows = Vec::new();
+            let mut seen = HashSet::new();
+
+            for c in &cols {
+                for node in iter_col(m, *c) {
+                    if seen.insert(m.row(node)) {
+                        rows.push(m.row(node));
+                    }
+                }
+            }
+            for c in cols.iter().cloned()
+                .chain(rows.iter().flat_map(|r| iter_full_row(m, m.row_start(*r)).map(|n| m.column(n)))) {
+                columns.entry(c).or_insert_with(|| {
+                    let nc = sub.matrix.add_column(m.bounds(c));
+                    sub.matrix.set_purity(nc, m.purity(c));
+                    sub.constraints.push(self.constraints[c].clone());
+                    sub.constraint_map.insert(self.constraints[c].clone(), nc);
+                    nc
+                });
+            }
+
+            for r in rows {
+                let entries: Vec<(ColumnIndex, Option<Color>)> = iter_full_row(m, m.row_start(r))
+                    .map(|n| (columns[&m.column(n)], m.color(n)))
+                    .collect();
+                let new_id = sub.matrix.add_row(&entries);
+                sub.actions.push(self.actions[r].clone());
+                sub.action_map.insert(self.get_action(r), new_id);
+            }
+            sub
+        }).collect()
+    }
+
+    /// Return the action associated with a row.
+    pub(crate) fn get_action(&self, row: RowIndex) -> A {
+        self.actions[row].action
+    }
+
+    /// Return the cost of the action associated with a row.
+    pub(crate) fn get_cost(&self, row: RowIndex) -> u64 {
+        self.actions[row].cost
+    }
+
+    /// Set the cost of an action, used by `Solver::min_cost_solution()`
+    /// and related methods. Actions cost nothing by default. Does
+    /// nothing if the action doesn't exist.
+    pub fn set_action_cost(&mut self, action: A, cost: u64) {
+        if let Some(i) = self.action_map.get(&action) {
+            self.actions[*i].cost = cost;
+        }
+    }
+
+    /// Return the cost of an action, if it exists.
+    pub fn action_cost(&self, action: A) -> Option<u64> {
+        self.get_action_row(action).map(|r| self.actions[r].cost)
+    }
+
+    /// Return a lower bound on the cost of the actions still needed
+    /// to satisfy every remaining constraint of `m`: each unsatisfied
+    /// constraint needs some number of actions from its column, none
+    /// of which can be cheaper than the cheapest remaining one.
+    pub(crate) fn cost_lower_bound(&self, m: &Matrix) -> u64 {
+        iter_columns(m)
+            .map(|c| {
+                let need = m.bounds(c).need() as u64;
+                let cheapest = iter_col(m, c).map(|n| self.get_cost(m.row(n))).min().unwrap_or(0);
+                cheapest.saturating_mul(need)
+            })
+            .max().unwrap_or(0)
+    }
+
+    /// Return true if the action exists and it is possible to
+    /// require it.
     pub fn can_require_row(&self, action: A) -> bool {
-        let act = &(self.actions.get(*self.action_map.get(&action).unwrap()).unwrap());
+        self.get_action_row(action).is_some_and(|r| !self.actions[r].required && self.find_conflict(r).is_none())
+    }
 
-        !act.iter().map(|node| { self.get_column(&node) }).any(|c| { c.borrow().is_already_chosen() })
+    /// Return the first column that prevents row `r` from being
+    /// selected, if any.
+    fn find_conflict(&self, r: RowIndex) -> Option<ColumnIndex> {
+        let m = &self.matrix;
+        iter_full_row(m, m.row_start(r))
+            .find(|n| m.rejects_color(m.column(*n), m.color(*n)))
+            .map(|n| m.column(n))
     }
 
-    /// Return the action row for a particular action
-    pub fn get_action_row(&self, action: A) -> Option<&Row<A>> {
-        self.action_map.get(&action)
-            .map(|i| self.actions.get(*i).unwrap())
+    /// Return the matrix row for a particular action
+    pub(crate) fn get_action_row(&self, action: A) -> Option<RowIndex> {
+        self.action_map.get(&action).cloned()
     }
 
     /// Require that a given action be part of the solution
-    pub fn require_row(&mut self, action: A) -> Result<(), String> {
-        let iter = {
-            let act = self.get_action_row(action).unwrap();
+    pub fn require_row(&mut self, action: A) -> Result<(), Error<A, C>> {
+        let r = self.get_action_row(action).ok_or(Error::UnknownAction(action))?;
 
-            if let Some(c) = act.iter().map(|node| { self.get_column(&node) }).find(|c| { c.borrow().is_already_chosen() })
-            {
-                return Err(format!("Could not require row; C {} already satisfied", c.borrow().column.unwrap()));
-            }
-            act.iter()
-        };
-
-        for n in iter {
-            //let ci = self.get_column(&n).borrow().column.unwrap();
-            cover_column(self.get_column(&n));
+        if self.actions[r].required {
+            return Err(Error::AlreadyRequired(action));
+        }
+        if let Some(c) = self.find_conflict(r) {
+            return Err(Error::Conflict { action, constraint: self.constraints[c].clone() });
         }
 
+        let node = self.matrix.row_start(r);
+        select_row(&mut self.matrix, node);
+        self.actions[r].required = true;
+
         Ok(())
     }
+
+    /// Undo `require_row()` for an action. Rows must be released in
+    /// the reverse order they were required, which is up to the
+    /// caller.
+    pub(crate) fn release_row(&mut self, action: A) {
+        let r = self.get_action_row(action).expect("required actions exist");
+        let node = self.matrix.row_start(r);
+        unselect_row(&mut self.matrix, node);
+        self.actions[r].required = false;
+    }
 }
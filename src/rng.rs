@@ -0,0 +1,45 @@
+/// A small, fast pseudo-random number generator (SplitMix64), so the
+/// crate doesn't need to depend on `rand`. The same seed always gives
+/// the same sequence.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Return the state of the generator; `Rng::new(state)` carries
+    /// on with the same sequence.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Return a number in `0..n`, which must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Return a number in `0..n`, which must not be 0, without the
+    /// bias of taking a remainder.
+    pub fn below_u128(&mut self, n: u128) -> u128 {
+        // 2^128 mod n; the values from there up split evenly.
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let x = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+            if x >= threshold {
+                return x % n;
+            }
+        }
+    }
+}
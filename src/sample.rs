@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use node::{Matrix, RowIndex, NONE};
+use iter::{iter_col, iter_full_row, iter_columns};
+use problem::{Constraint, Action};
+use solver::{Solver, FrameState};
+use chooser::{ColumnChooser, choose_column};
+use rng::Rng;
+
+/// Index of a state in `Sampler::states`.
+type StateId = usize;
+
+/// The state with no solutions, which is never branched into.
+const DEAD: StateId = 0;
+
+/// The state with nothing left to satisfy, which has one solution:
+/// the empty one.
+const DONE: StateId = 1;
+
+/// A `State` is a node of the search, shared by every path that leads
+/// to the same remaining matrix.
+#[derive(Debug, Clone)]
+struct State {
+    count: u128,
+    /// The rows that can be selected next (or None, for selecting no
+    /// more rows in a bounded column), with the states they lead to.
+    /// Only branches with solutions are kept.
+    children: Vec<(Option<RowIndex>, StateId)>
+}
+
+/// A `Sampler` draws solutions of a problem uniformly at random.
+///
+/// Building the sampler walks the search tree once, merging nodes
+/// whose remaining matrix is the same, and records the exact number
+/// of solutions below each one. Each sample then walks down from the
+/// root, picking every branch with probability proportional to its
+/// number of solutions, so every solution is equally likely, and a
+/// sample costs no more than the depth of the search.
+#[derive(Debug, Clone)]
+pub struct Sampler<A: Action> {
+    states: Vec<State>,
+    root: StateId,
+    actions: Vec<A>,
+    partial: Vec<A>,
+    rng: Rng
+}
+
+impl<A: Action> Sampler<A> {
+    /// Return the number of solutions.
+    pub fn num_solutions(&self) -> u128 {
+        self.states[self.root].count
+    }
+
+    /// Restart the random number generator from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Draw a solution, if there are any. Any previously required
+    /// actions come first.
+    pub fn sample(&mut self) -> Option<Vec<A>> {
+        if self.num_solutions() == 0 {
+            return None;
+        }
+
+        let mut sol = self.partial.clone();
+        let mut s = self.root;
+        while s != DONE {
+            let state = &self.states[s];
+            let mut pick = self.rng.below_u128(state.count);
+            for &(r, child) in &state.children {
+                let n = self.states[child].count;
+                if pick < n {
+                    sol.extend(r.map(|r| self.actions[r]));
+                    s = child;
+                    break;
+                }
+                pick -= n;
+            }
+        }
+        Some(sol)
+    }
+}
+
+impl<A: Action> Iterator for Sampler<A> {
+    type Item = Vec<A>;
+
+    /// Draw another solution; the samples never run out, unless there
+    /// are no solutions at all.
+    fn next(&mut self) -> Option<Vec<A>> {
+        self.sample()
+    }
+}
+
+/// Return a key identifying the remaining search at the current state
+/// of the matrix: the columns that may still be branched on, the
+/// live rows that could satisfy them, and the usage of every column
+/// those rows occupy. Rows that can no longer be selected, and columns
+/// they alone occupy, don't affect the search.
+fn state_key(m: &Matrix) -> Vec<usize> {
+    let mut key: Vec<usize> = Vec::new();
+    let mut rows: Vec<RowIndex> = Vec::new();
+    for c in iter_columns(m) {
+        key.push(c);
+        key.push(m.bounds(c).used);
+        rows.extend(iter_col(m, c).map(|n| m.row(n)));
+    }
+    rows.sort_unstable();
+    rows.dedup();
+
+    let mut columns = Vec::new();
+    for r in &rows {
+        columns.extend(iter_full_row(m, m.row_start(*r)).map(|n| m.column(n)));
+    }
+    columns.sort_unstable();
+    columns.dedup();
+
+    key.push(NONE);
+    key.extend(rows);
+    key.push(NONE);
+    for c in columns {
+        key.push(c);
+        key.push(m.bounds(c).used);
+        key.push(m.purity(c).map_or(NONE, |(color, _)| color));
+    }
+    key
+}
+
+/// Builds the states of a `Sampler`.
+struct Builder<'a, C: 'a, H> {
+    names: &'a [C],
+    chooser: H,
+    states: Vec<State>,
+    memo: HashMap<Vec<usize>, StateId>
+}
+
+impl<'a, C, H: ColumnChooser<C>> Builder<'a, C, H> {
+    fn build(&mut self, m: &mut Matrix) -> StateId {
+        let key = state_key(m);
+        if let Some(s) = self.memo.get(&key) {
+            return *s;
+        }
+
+        let s = match choose_column(m, self.names, &mut self.chooser) {
+            None => DONE,
+            Some(c) if m.degree(c) == 0 => DEAD,
+            Some(c) => {
+                let mut state = State { count: 0, children: Vec::new() };
+                let mut frame = FrameState::new(m, c);
+                while let Some(node) = frame.advance(m) {
+                    let child = self.build(m);
+                    if child != DEAD {
+                        state.count = state.count.checked_add(self.states[child].count)
+                            .expect("too many solutions to sample");
+                        state.children.push((node.map(|n| m.row(n)), child));
+                    }
+                }
+                frame.finish(m);
+
+                if state.count == 0 {
+                    DEAD
+                } else {
+                    self.states.push(state);
+                    self.states.len() - 1
+                }
+            }
+        };
+        self.memo.insert(key, s);
+        s
+    }
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C> + Clone> Solver<A, C, H> {
+    /// Return a sampler drawing solutions (including any previously
+    /// required actions) uniformly at random, starting from `seed`.
+    ///
+    /// This counts every solution, so it takes about as long as
+    /// `count_solutions()`, less when large parts of the search
+    /// repeat; after that, samples are cheap.
+    pub fn sampler(&self, seed: u64) -> Sampler<A> {
+        let mut builder = Builder {
+            names: &self.problem.constraints,
+            chooser: self.chooser.clone(),
+            states: vec![State { count: 0, children: Vec::new() },
+                         State { count: 1, children: Vec::new() }],
+            memo: HashMap::new()
+        };
+        let mut m = self.problem.matrix.clone();
+        let root = builder.build(&mut m);
+
+        let actions = (0..self.problem.num_actions()).map(|r| self.problem.get_action(r)).collect();
+        Sampler { states: builder.states, root, actions,
+                  partial: self.partial_solution.clone(), rng: Rng::new(seed) }
+    }
+}
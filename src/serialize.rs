@@ -0,0 +1,147 @@
+//! `Serialize` and `Deserialize` for problems and solvers, with the
+//! `serde` feature.
+//!
+//! A problem is stored as its definition: its constraints, in the
+//! order they were added, with their bounds, and its actions, with
+//! the constraints they satisfy and their costs. Rows required
+//! directly on a `Problem` (via `require_row()`) are not kept; a
+//! `Solver` is stored as its problem and its required actions.
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error as DeError;
+
+use node::Color;
+use iter::iter_full_row;
+use problem::{Problem, Constraint, Action};
+use solver::Solver;
+use chooser::ColumnChooser;
+use error::Error;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Problem")]
+struct ProblemDef<A, C> {
+    constraints: Vec<ConstraintDef<C>>,
+    actions: Vec<ActionDef<A, C>>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Constraint")]
+struct ConstraintDef<C> {
+    constraint: C,
+    lo: usize,
+    hi: usize
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Action")]
+struct ActionDef<A, C> {
+    action: A,
+    constraints: Vec<C>,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    colored: Vec<(C, Color)>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    cost: u64
+}
+
+fn is_zero(x: &u64) -> bool {
+    *x == 0
+}
+
+#[derive(Serialize)]
+#[serde(rename = "Solver")]
+struct SolverRef<'a, A: Action + 'a, C: Constraint + 'a> {
+    problem: &'a Problem<A, C>,
+    required: &'a [A]
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Solver")]
+struct SolverDef<A: Action, C: Constraint> {
+    problem: Problem<A, C>,
+    required: Vec<A>
+}
+
+/// Describe an error without requiring `A` and `C` to be `Debug`.
+fn describe<A, C>(e: Error<A, C>) -> &'static str {
+    match e {
+        Error::UnknownAction(_) => "unknown action",
+        Error::Conflict { .. } => "conflicting required actions",
+        Error::AlreadyRequired(_) => "action required twice",
+        Error::DuplicateAction(_) => "duplicate action",
+        Error::EmptyAction(_) => "action satisfies no constraint",
+        Error::ColoredPrimary { .. } => "colored constraint is not secondary",
+        _ => "invalid problem"
+    }
+}
+
+impl<A: Action + Serialize, C: Constraint + Serialize> Serialize for Problem<A, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let m = &self.matrix;
+        let constraints = self.constraints.iter().enumerate()
+            .map(|(c, constraint)| {
+                let b = m.bounds(c);
+                ConstraintDef { constraint, lo: b.lo, hi: b.hi }
+            })
+            .collect();
+
+        let actions = (0..self.num_actions())
+            .map(|r| {
+                let mut def = ActionDef { action: self.get_action(r), constraints: Vec::new(),
+                                          colored: Vec::new(), cost: self.get_cost(r) };
+                for n in iter_full_row(m, m.row_start(r)) {
+                    let constraint = &self.constraints[m.column(n)];
+                    match m.color(n) {
+                        None => def.constraints.push(constraint),
+                        Some(color) => def.colored.push((constraint, color))
+                    }
+                }
+                def
+            })
+            .collect();
+
+        ProblemDef { constraints, actions }.serialize(serializer)
+    }
+}
+
+impl<'de, A, C> Deserialize<'de> for Problem<A, C>
+    where A: Action + Deserialize<'de>, C: Constraint + Deserialize<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let def: ProblemDef<A, C> = ProblemDef::deserialize(deserializer)?;
+        let mut p = Problem::new();
+
+        for c in def.constraints {
+            if c.hi == 0 || c.lo > c.hi {
+                return Err(D::Error::custom("invalid constraint bounds"));
+            }
+            p.add_bounded_constraint(&c.constraint, c.lo, c.hi);
+        }
+        for a in def.actions {
+            p.add_colored_action(a.action, &a.constraints, &a.colored)
+                .map_err(|e| D::Error::custom(describe(e)))?;
+            p.set_action_cost(a.action, a.cost);
+        }
+        Ok(p)
+    }
+}
+
+impl<A, C, H> Serialize for Solver<A, C, H>
+    where A: Action + Serialize, C: Constraint + Serialize {
+    /// Only the problem and the required actions are stored; the
+    /// chooser and any limits are not.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SolverRef { problem: &self.problem, required: &self.partial_solution }.serialize(serializer)
+    }
+}
+
+impl<'de, A, C, H> Deserialize<'de> for Solver<A, C, H>
+    where A: Action + Deserialize<'de>, C: Constraint + Deserialize<'de>,
+          H: ColumnChooser<C> + Clone + Default {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let def: SolverDef<A, C> = SolverDef::deserialize(deserializer)?;
+        let mut solver = Solver::with_chooser(def.problem, H::default());
+        for a in def.required {
+            solver.require_action(a).map_err(|e| D::Error::custom(describe(e)))?;
+        }
+        Ok(solver)
+    }
+}
@@ -1,154 +1,678 @@
-use node::{OwnedNode, WeakNode};
+use std::sync::Arc;
+
+use node::{Matrix, NodeIndex, ColumnIndex};
 use problem::{Problem, Constraint, Action};
-use iter::{iter_col, ColumnIterator};
-use cover::{try_cover_column, cover_column, uncover_column, try_cover_row, cover_row, uncover_row};
+use cover::{cover_column, uncover_column, cover_row, uncover_row, select_row, unhide_row};
+use component::components;
+use chooser::{ColumnChooser, Mrv, MrvRandom, choose_column};
+use iter::{iter_col, iter_full_row};
+use rng::Rng;
+use error::Error;
+use checkpoint::{Checkpoint, Writer, Reader};
+use limits::{SearchLimits, SearchResult, SearchStats, StopReason, Budget, Progress, Observer};
 
 /// A `Solver` consumes a problem and computes solutions to the exact
 /// cover problem.
-pub struct Solver<A: Action, C: Constraint> {
-    problem: Problem<A, C>,
-    partial_solution: Vec<A>,
+///
+/// The constraint to branch on at each node of the search is picked
+/// by a `ColumnChooser`, `Mrv` by default.
+#[derive(Clone)]
+pub struct Solver<A: Action, C: Constraint, H = Mrv> {
+    pub(crate) problem: Problem<A, C>,
+    pub(crate) partial_solution: Vec<A>,
+    pub(crate) decompose: bool,
+    pub(crate) chooser: H,
+    pub(crate) shuffle: Option<Rng>,
+    pub(crate) limits: SearchLimits,
+    pub(crate) progress: Option<Observer>,
+    /// The number of required actions when each open scope was
+    /// pushed.
+    scopes: Vec<usize>
 }
 
+/// A `FrameState` is one level of the search: a chosen column, and
+/// the row of that column currently selected.
+///
+/// If the chosen column can only be satisfied by one more action, the
+/// column is covered up front, as in plain Algorithm X. Otherwise,
+/// each row is fully selected in turn, and rows that have already
+/// been tried stay hidden while their siblings are explored, so that
+/// the same set of rows is never produced in a different order.
+///
+/// If the column's lower bound is already met, the last branch
+/// selects no row at all, and takes the column out of the header
+/// list, as in Knuth's Algorithm M: no further action satisfies it.
+///
+/// Rows are tried from the top of the column down, unless the frame
+/// has been shuffled, in which case `order` holds the rows left to
+/// try (the next one last).
 #[derive(Debug)]
-struct FrameState {
-    iter: ColumnIterator,
-    column: OwnedNode,
-    row: Option<WeakNode>
+pub(crate) struct FrameState {
+    column: ColumnIndex,
+    cursor: NodeIndex,
+    last_slot: bool,
+    may_skip: bool,
+    skipping: bool,
+    row: Option<NodeIndex>,
+    tried: Vec<NodeIndex>,
+    order: Option<Vec<NodeIndex>>
 }
 
-impl Drop for FrameState {
-    fn drop(&mut self) {
-        // uncover the column
-        uncover_column(&self.column);
+impl FrameState {
+    pub(crate) fn new(m: &mut Matrix, column: ColumnIndex) -> FrameState {
+        let bounds = m.bounds(column);
+        let last_slot = bounds.remaining() == 1;
+        if last_slot {
+            cover_column(m, column);
+        }
+        FrameState { column, cursor: m.header(column),
+                     last_slot, may_skip: bounds.need() == 0, skipping: false,
+                     row: None, tried: Vec::new(), order: None }
+    }
+
+    /// Try the rows of the column in random order. Must be called
+    /// before the first `advance()`.
+    pub(crate) fn shuffle(&mut self, m: &Matrix, rng: &mut Rng) {
+        let mut order: Vec<NodeIndex> = iter_col(m, self.column).collect();
+        for i in (1..order.len()).rev() {
+            order.swap(i, rng.below(i + 1));
+        }
+        self.order = Some(order);
+    }
+
+    /// Release the current branch, if any, and take the next one:
+    /// `Some(Some(node))` selects the row of `node`, `Some(None)`
+    /// gives up on the column, and `None` means there are no branches
+    /// left.
+    pub(crate) fn advance(&mut self, m: &mut Matrix) -> Option<Option<NodeIndex>> {
+        self.release(m);
+        if self.skipping {
+            return None;
+        }
+
+        let next = match self.order {
+            Some(ref mut order) => order.pop(),
+            None => {
+                let node = m.down(self.cursor);
+                if node == m.header(self.column) { None } else { Some(node) }
+            }
+        };
+        let node = match next {
+            Some(node) => node,
+            None => return self.skip(m)
+        };
+        self.cursor = node;
+
+        if self.last_slot {
+            cover_row(m, node);
+        } else {
+            select_row(m, node);
+        }
+        self.row = Some(node);
+        Some(Some(node))
+    }
+
+    /// Take the branch selecting no more rows, if the column allows
+    /// it. Every row has been tried, so in the covered column of the
+    /// last slot there's nothing left to do; otherwise the rows are
+    /// all hidden, and only the header has to go.
+    fn skip(&mut self, m: &mut Matrix) -> Option<Option<NodeIndex>> {
+        if !self.may_skip {
+            return None;
+        }
+        if !self.last_slot {
+            let h = m.header(self.column);
+            m.remove_from_row(h);
+        }
+        self.skipping = true;
+        Some(None)
+    }
 
-        // uncover the row, if there is one
-        if let Some(ref node) = self.row {
-            uncover_row(node);
+    fn release(&mut self, m: &mut Matrix) {
+        if let Some(node) = self.row.take() {
+            uncover_row(m, node);
+            if !self.last_slot {
+                // Keep the row hidden for the rest of the frame.
+                uncover_column(m, self.column);
+                self.tried.push(node);
+            }
+        } else if self.skipping && !self.last_slot {
+            let h = m.header(self.column);
+            m.reinsert_into_row(h);
         }
     }
+
+    /// Restore the matrix to its state before the frame was created.
+    pub(crate) fn finish(mut self, m: &mut Matrix) {
+        self.release(m);
+
+        for node in self.tried.iter().rev() {
+            unhide_row(m, *node);
+        }
+
+        if self.last_slot {
+            uncover_column(m, self.column);
+        }
+    }
+
+    /// Record the position of the frame.
+    fn save(&self, m: &Matrix, w: &mut Writer) {
+        w.usize(self.column);
+        w.list(self.tried.iter().map(|n| m.row(*n)));
+        w.option(self.row.map(|n| m.row(n) as u64));
+        w.bool(self.skipping);
+        w.option_list(self.order.as_ref().map(|order| order.iter().map(|n| m.row(*n))));
+    }
+
+    /// Create a frame at the position recorded by `save()`, selecting
+    /// the same rows in the same order. `m` must be in the state the
+    /// saved frame was created in.
+    fn restore(m: &mut Matrix, r: &mut Reader, num_rows: usize) -> Option<FrameState> {
+        let column = r.index(m.num_columns())?;
+        let tried = r.list(num_rows)?;
+        let row = r.option_index(num_rows)?;
+        let skipping = r.bool()?;
+        let order = r.option_list(num_rows)?;
+
+        // The node of each row in the column.
+        let node = |m: &Matrix, row: usize| iter_full_row(m, m.row_start(row)).find(|n| m.column(*n) == column);
+        let mut frame = FrameState::new(m, column);
+        match order {
+            Some(order) => {
+                // Rows are popped from the end of the order, so put
+                // the rows already selected last.
+                let nodes = order.into_iter().chain(row).chain(tried.iter().rev().cloned())
+                    .map(|r| node(m, r))
+                    .collect::<Option<Vec<NodeIndex>>>()?;
+                frame.order = Some(nodes);
+                for _ in 0..tried.len() + row.iter().len() {
+                    frame.advance(m)?;
+                }
+            },
+            None => {
+                if let Some(row) = row {
+                    loop {
+                        let n = frame.advance(m)??;
+                        if m.row(n) == row {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        if skipping {
+            // Go through any rows left to the last branch.
+            while frame.advance(m)?.is_some() {}
+        }
+
+        if frame.row.map(|n| m.row(n)) != row || frame.skipping != skipping
+            || frame.tried.iter().map(|n| m.row(*n)).ne(tried) {
+            return None;
+        }
+        Some(frame)
+    }
 }
 
-pub struct SolutionIterator<A: Action, C: Constraint> {
+/// A `Search` walks the search tree below the current state of a
+/// problem's matrix, yielding each solution (prefixed with `partial`).
+pub(crate) struct Search<A: Action, C: Constraint, H> {
     problem: Problem<A, C>,
+    chooser: H,
+    shuffle: Option<Rng>,
     partial: Vec<A>,
     current_solution: Vec<A>,
     iter_stack: Vec< FrameState >,
-    running: bool
+    running: bool,
+    base_updates: u64
 }
 
-impl<A: Action, C: Constraint>  Drop for SolutionIterator<A, C> {
-    fn drop(&mut self) {
-        while !self.iter_stack.is_empty() {
-            self.iter_stack.pop();
-        }
-    }
-}
-
-impl <A: Action, C: Constraint> SolutionIterator<A, C> {
-    pub fn new(problem: Problem<A, C>) -> SolutionIterator<A, C> {
-        Self::from_solver(Solver::new(problem))
-    }
-
-    pub fn from_solver(solver: Solver<A, C>) -> SolutionIterator<A, C> {
-        SolutionIterator { problem: solver.problem, 
-                           current_solution: solver.partial_solution.clone(),
-                           partial: solver.partial_solution, 
-                           iter_stack: Vec::new(),
-                           running: false}
+impl <A: Action, C: Constraint, H: ColumnChooser<C>> Search<A, C, H> {
+    pub(crate) fn new(problem: Problem<A, C>, chooser: H, shuffle: Option<Rng>, partial: Vec<A>) -> Search<A, C, H> {
+        let base_updates = problem.matrix.updates();
+        Search { problem, chooser, shuffle,
+                 current_solution: partial.clone(),
+                 partial,
+                 iter_stack: Vec::new(),
+                 running: false,
+                 base_updates }
     }
 
     // If init returns a solution, that's the only solution
-    fn init(&mut self) -> Option<Vec<A>> {
-        let c = self.problem.choose_column();
-        match c {
-            None =>  Some(self.partial.clone()),
+    fn init(&mut self, budget: &mut Budget) -> Option<Vec<A>> {
+        let m = &mut self.problem.matrix;
+        match choose_column(m, &self.problem.constraints, &mut self.chooser) {
+            None => {
+                budget.solution();
+                Some(self.partial.clone())
+            },
             Some(c) => {
-                if c.borrow().get_count().unwrap() > 0 {
-                    cover_column(&c);
-                    self.iter_stack.push(FrameState { iter: iter_col(&c), 
-                                                      column: c.clone(),
-                                                      row: None })
+                if m.degree(c) > 0 {
+                    budget.frame(0, m.branches(c));
+                    let frame = new_frame(m, c, &mut self.shuffle);
+                    self.iter_stack.push(frame);
                 }
                 None
             }
         }
     }
-}
 
-impl<A: Action, C: Constraint> Iterator for SolutionIterator<A, C>  {
-    type Item = Vec<A>;
+    /// Record the position of the search.
+    fn save(&self, w: &mut Writer) {
+        w.bool(self.running);
+        w.option(self.shuffle.as_ref().map(|r| r.state()));
+        w.usize(self.iter_stack.len());
+        for frame in &self.iter_stack {
+            frame.save(&self.problem.matrix, w);
+        }
+    }
+
+    /// Move a search that hasn't started to the position recorded by
+    /// `save()`.
+    fn restore(&mut self, r: &mut Reader) -> Option<()> {
+        self.running = r.bool()?;
+        if let Some(state) = r.option()? {
+            self.shuffle = Some(Rng::new(state));
+        }
+        let num_rows = self.problem.num_actions();
+        for _ in 0..r.usize()? {
+            let frame = FrameState::restore(&mut self.problem.matrix, r, num_rows)?;
+            if let Some(node) = frame.row {
+                let row = self.problem.matrix.row(node);
+                self.current_solution.push(self.problem.get_action(row));
+            }
+            self.iter_stack.push(frame);
+        }
+        Some(())
+    }
 
+    /// Return the number of link updates made by the search so far.
+    pub(crate) fn updates(&self) -> u64 {
+        self.problem.matrix.updates() - self.base_updates
+    }
 
-    fn next(&mut self) -> Option<Vec<A>> {
+    /// Return the next solution, or stop early if `budget` runs out.
+    /// Stopping leaves the search as it was, so it can be resumed.
+    pub(crate) fn next_within(&mut self, budget: &mut Budget) -> Result<Option<Vec<A>>, StopReason> {
         if !self.running {
-            self.init();
             self.running = true;
+            if let Some(s) = self.init(budget) {
+                return Ok(Some(s));
+            }
         }
 
-        let mut s = None;
-
-        // At each step, take the next action in the iterator. Try to push a new state onto the column.
-        while !self.iter_stack.is_empty() {
-            let next_action = {
-                let n = self.iter_stack.len();
-                self.iter_stack[n - 1].iter.next()
-            };
+        // At each step, take the next action in the top frame. Try to push a new frame onto the stack.
+        while let Some(frame) = self.iter_stack.last_mut() {
+            budget.visit()?;
 
-            // Take the next action.
-            if let Some(action_node) = next_action {
-                let a = self.problem.get_action(&action_node);
-                // {
-                //     let sa = action_node.upgrade().unwrap();
-                //     println!("trying action {}", sa.borrow().get_row().unwrap());
-                // }
+            let m = &mut self.problem.matrix;
+            if frame.row.is_some() {
+                self.current_solution.pop();
+            }
 
+            // Take the next action, if any.
+            if let Some(branch) = frame.advance(m) {
                 // add the action the current solution
-                self.current_solution.push(a);
-                cover_row(&action_node);
+                if let Some(action_node) = branch {
+                    let r = m.row(action_node);
+                    self.current_solution.push(self.problem.get_action(r));
+                }
+                let depth = self.iter_stack.len();
+                budget.node(depth);
 
                 // Choose a new constraint
-                let c = self.problem.choose_column();
-
-                match c {
-                    // If there's no column to choose, we've found a result.
+                let m = &mut self.problem.matrix;
+                match choose_column(m, &self.problem.constraints, &mut self.chooser) {
+                    // If there's no column to choose, we've found a
+                    // result. The row is released on the next call.
                     None => {
-                        s = Some(self.current_solution.clone());
-
-                        // We need to uncover the row ourselves,
-                        // since we don't FrameState to do it for
-                        // us.
-                        uncover_row(&action_node);
-                        self.current_solution.pop();
+                        budget.solution();
+                        return Ok(Some(self.current_solution.clone()));
                     },
                     // Otherwise, check to see if there are still options left.
                     Some(c) => {
                         // If there are, push a new frame.
-                        cover_column(&c);
-                        self.iter_stack.push(FrameState { iter: iter_col(&c), 
-                                                          column: c.clone(),
-                                                          row: Some(action_node) });
+                        if m.degree(c) > 0 {
+                            budget.frame(depth, m.branches(c));
+                            let frame = new_frame(m, c, &mut self.shuffle);
+                            self.iter_stack.push(frame);
+                        }
                     }
                 }
             } else {
-                let r = self.iter_stack.pop();
-                if let Some(_) = r {
-                    self.current_solution.pop();
+                let frame = self.iter_stack.pop().unwrap();
+                frame.finish(m);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C>> Iterator for Search<A, C, H>  {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Vec<A>> {
+        self.next_within(&mut Budget::default()).unwrap()
+    }
+}
+
+/// A `Part` is the search of one independent component of a problem.
+///
+/// Every component but the first is iterated over once per solution
+/// of the components before it, so its solutions are kept after the
+/// first pass instead of being searched for again.
+struct Part<A: Action, C: Constraint, H> {
+    search: Search<A, C, H>,
+    keep: bool,
+    found: Vec<Vec<A>>,
+    pos: usize,
+    current: Vec<A>
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C>> Part<A, C, H> {
+    fn new(problem: Problem<A, C>, chooser: H, shuffle: Option<Rng>, keep: bool) -> Part<A, C, H> {
+        Part { search: Search::new(problem, chooser, shuffle, Vec::new()), keep,
+               found: Vec::new(), pos: 0, current: Vec::new() }
+    }
+
+    /// Move on to the next solution of the component, returning false
+    /// at the end of the pass.
+    fn advance(&mut self, budget: &mut Budget) -> Result<bool, StopReason> {
+        let sol = if self.pos < self.found.len() {
+            Some(self.found[self.pos].clone())
+        } else {
+            let sol = self.search.next_within(budget)?;
+            if let (Some(s), true) = (&sol, self.keep) {
+                self.found.push(s.clone());
+            }
+            sol
+        };
+
+        match sol {
+            Some(s) => {
+                self.pos += 1;
+                self.current = s;
+                Ok(true)
+            },
+            None => Ok(false)
+        }
+    }
+
+    /// Start a new pass over the solutions of the component, which
+    /// have all been found by now.
+    fn rewind(&mut self) {
+        self.current = self.found[0].clone();
+        self.pos = 1;
+    }
+
+    fn save(&self, w: &mut Writer) {
+        let p = &self.search.problem;
+        let rows = |sol: &Vec<A>| sol.iter().map(|a| p.get_action_row(*a).unwrap()).collect::<Vec<_>>();
+        w.usize(self.pos);
+        w.list(rows(&self.current));
+        w.usize(self.found.len());
+        for sol in &self.found {
+            w.list(rows(sol));
+        }
+        self.search.save(w);
+    }
+
+    fn restore(&mut self, r: &mut Reader) -> Option<()> {
+        let num_rows = self.search.problem.num_actions();
+        self.pos = r.usize()?;
+        self.current = r.list(num_rows)?.into_iter().map(|row| self.search.problem.get_action(row)).collect();
+        for _ in 0..r.usize()? {
+            let sol = r.list(num_rows)?.into_iter().map(|row| self.search.problem.get_action(row)).collect();
+            self.found.push(sol);
+        }
+        if self.pos > self.found.len() && self.keep {
+            return None;
+        }
+        self.search.restore(r)
+    }
+}
+
+/// A `SolutionIterator` iterates over the solutions of a problem.
+///
+/// If `Solver::set_decompose()` was set and the problem falls apart
+/// into independent components, each component is searched on its
+/// own, and the solutions are the combinations of the solutions of
+/// the components, produced lazily (the last component varying
+/// fastest). The solutions of every component but the first are kept
+/// in memory, and in checkpoints.
+///
+/// If the solver had search limits (see `Solver::set_limits()`), they
+/// apply to the iteration as a whole: once they are reached, `next()`
+/// returns None, and `stopped()` tells why.
+pub struct SolutionIterator<A: Action, C: Constraint, H = Mrv> {
+    partial: Vec<A>,
+    /// The size of the problem, to tell checkpoints of other problems.
+    num_actions: usize,
+    num_constraints: usize,
+    parts: Vec<Part<A, C, H>>,
+    started: usize,
+    running: bool,
+    done: bool,
+    budget: Budget,
+    stopped: Option<StopReason>
+}
+
+impl <A: Action, C: Constraint> SolutionIterator<A, C> {
+    pub fn new(problem: Problem<A, C>) -> SolutionIterator<A, C> {
+        Self::from_solver(Solver::new(problem))
+    }
+}
+
+impl <A: Action, C: Constraint, H: ColumnChooser<C> + Clone> SolutionIterator<A, C, H> {
+    pub fn with_chooser(problem: Problem<A, C>, chooser: H) -> SolutionIterator<A, C, H> {
+        Self::from_solver(Solver::with_chooser(problem, chooser))
+    }
+
+    pub fn from_solver(solver: Solver<A, C, H>) -> SolutionIterator<A, C, H> {
+        let chooser = solver.chooser;
+        let mut shuffle = solver.shuffle;
+        let num_actions = solver.problem.num_actions();
+        let num_constraints = solver.problem.num_constraints();
+        let comps = if solver.decompose { components(&solver.problem.matrix) } else { Vec::new() };
+        let problems = if comps.len() > 1 {
+            solver.problem.split(comps)
+        } else {
+            vec![solver.problem]
+        };
+
+        let parts = problems.into_iter().enumerate()
+            .map(|(i, p)| {
+                let rng = shuffle.as_mut().map(|r| Rng::new(r.next_u64()));
+                Part::new(p, chooser.clone(), rng, i > 0)
+            })
+            .collect();
+        let mut budget = Budget::new(solver.limits);
+        budget.progress = solver.progress;
+        SolutionIterator { partial: solver.partial_solution, num_actions, num_constraints, parts, started: 0,
+                           running: false, done: false, budget, stopped: None }
+    }
+
+    /// Call `observer` with the progress of the iteration every
+    /// `interval` nodes; see `Solver::set_progress()`.
+    pub fn set_progress<F>(&mut self, interval: u64, observer: F)
+        where F: Fn(&Progress) + Send + Sync + 'static {
+        self.budget.progress = Some(Observer { interval: interval.max(1), report: Arc::new(observer) });
+    }
+
+    /// Replace the search limits, e.g. to resume an iteration that
+    /// gave up. Node counts start over, but `stats()` carries on.
+    pub fn set_limits(&mut self, limits: SearchLimits) {
+        self.budget.set_limits(limits);
+    }
+
+    /// Return statistics on the search so far.
+    pub fn stats(&self) -> SearchStats {
+        let mut stats = self.budget.stats.clone();
+        stats.updates = self.parts.iter().map(|p| p.search.updates()).sum();
+        stats
+    }
+
+    /// Record the position of the iteration, to be picked up later by
+    /// `resume()`.
+    pub fn checkpoint(&self) -> Checkpoint {
+        let mut w = Writer::new();
+        w.usize(self.num_actions);
+        w.usize(self.num_constraints);
+        w.usize(self.partial.len());
+        w.usize(self.started);
+        w.bool(self.running);
+        w.bool(self.done);
+        w.usize(self.parts.len());
+        for p in &self.parts {
+            p.save(&mut w);
+        }
+        w.finish()
+    }
+
+    /// Create an iterator over the solutions of `solver` that picks up
+    /// where the iteration recorded in `checkpoint` was, producing
+    /// exactly the solutions it hadn't produced yet.
+    ///
+    /// The solver must be set up as the one the checkpoint was taken
+    /// from: same problem, same required actions, same chooser, same
+    /// `set_decompose()`.
+    /// Randomized searches carry on with a fresh chooser, so the order
+    /// of the remaining solutions may differ, but not the solutions.
+    /// An error is returned if the checkpoint doesn't fit the solver.
+    pub fn resume(solver: Solver<A, C, H>, checkpoint: &Checkpoint) -> Result<SolutionIterator<A, C, H>, Error<A, C>> {
+        let mut iter = SolutionIterator::from_solver(solver);
+        iter.restore(checkpoint).ok_or(Error::InvalidCheckpoint)?;
+        Ok(iter)
+    }
+
+    fn restore(&mut self, checkpoint: &Checkpoint) -> Option<()> {
+        let mut r = Reader::new(checkpoint)?;
+        if r.usize()? != self.num_actions || r.usize()? != self.num_constraints
+            || r.usize()? != self.partial.len() {
+            return None;
+        }
+        self.started = r.index(self.parts.len() + 1)?;
+        self.running = r.bool()?;
+        self.done = r.bool()?;
+        if r.usize()? != self.parts.len() {
+            return None;
+        }
+        for p in &mut self.parts {
+            p.restore(&mut r)?;
+        }
+        if r.is_empty() { Some(()) } else { None }
+    }
+
+    /// Return the reason the last call to `next()` or `try_next()`
+    /// gave up, if it did.
+    pub fn stopped(&self) -> Option<StopReason> {
+        self.stopped
+    }
+
+    /// Return the next solution, distinguishing the end of the
+    /// solutions from giving up on the search limits. After giving up,
+    /// the iteration can be resumed where it stopped.
+    pub fn try_next(&mut self) -> SearchResult<Vec<A>> {
+        let r = self.step();
+        self.stopped = r.as_ref().err().cloned();
+        r.into()
+    }
+
+    fn step(&mut self) -> Result<Option<Vec<A>>, StopReason> {
+        if self.done {
+            return Ok(None);
+        }
+
+        if !self.running {
+            while self.started < self.parts.len() {
+                if !self.parts[self.started].advance(&mut self.budget)? {
+                    self.done = true;
+                    return Ok(None);
                 }
+                self.started += 1;
             }
-                
-            // if we found a solution during the iteration, return it
-            if s.is_some() {
-                return s;
+            self.running = true;
+        } else {
+            // Advance the last component that has solutions left,
+            // and restart the ones after it.
+            let mut i = self.parts.len();
+            loop {
+                if i == 0 {
+                    self.done = true;
+                    return Ok(None);
+                }
+                i -= 1;
+                if self.parts[i].advance(&mut self.budget)? {
+                    break;
+                }
             }
+            for p in &mut self.parts[i + 1..] {
+                p.rewind();
+            }
+        }
+
+        let mut sol = self.partial.clone();
+        for p in &self.parts {
+            sol.extend_from_slice(&p.current);
         }
-        None
+        Ok(Some(sol))
+    }
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C> + Clone> Iterator for SolutionIterator<A, C, H>  {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Vec<A>> {
+        self.try_next().found()
     }
 }
 
 impl<A: Action, C: Constraint> Solver<A, C> {
     pub fn new(problem: Problem<A, C>) -> Solver<A, C> {
-        Solver { problem: problem, partial_solution: Vec::new() }
+        Solver::with_chooser(problem, Mrv)
+    }
+}
+
+impl<A: Action, C: Constraint, H: ColumnChooser<C> + Clone> Solver<A, C, H> {
+    /// Create a solver that branches on the constraints picked by
+    /// `chooser`.
+    pub fn with_chooser(problem: Problem<A, C>, chooser: H) -> Solver<A, C, H> {
+        Solver { problem, partial_solution: Vec::new(), decompose: false, chooser, shuffle: None,
+                 limits: SearchLimits::new(), progress: None, scopes: Vec::new() }
+    }
+
+    /// Switch to branching on the constraints picked by `chooser`,
+    /// keeping any required actions.
+    pub fn use_chooser<H2: ColumnChooser<C> + Clone>(self, chooser: H2) -> Solver<A, C, H2> {
+        Solver { problem: self.problem, partial_solution: self.partial_solution,
+                 decompose: self.decompose, chooser, shuffle: self.shuffle,
+                 limits: self.limits, progress: self.progress, scopes: self.scopes }
+    }
+
+    /// Randomize the search: ties between constraints are broken at
+    /// random (see `MrvRandom`), and the actions that could satisfy a
+    /// constraint are tried in random order. The same seed always
+    /// gives the same search, so iterating over the returned solver
+    /// produces the solutions in a varied but reproducible order.
+    pub fn randomized(self, seed: u64) -> Solver<A, C, MrvRandom> {
+        let (chooser, shuffle) = random_search(seed);
+        let mut solver = self.use_chooser(chooser);
+        solver.shuffle = Some(shuffle);
+        solver
+    }
+
+    /// Choose whether to look for independent components at every
+    /// node of the search when counting solutions, rather than only
+    /// before the search starts, and whether to iterate over the
+    /// solutions of independent components separately (see
+    /// `SolutionIterator`).
+    ///
+    /// Splitting costs a pass over the remaining matrix at every
+    /// node, so it only pays off for problems that fall apart as
+    /// actions are selected. Iterating over components separately
+    /// saves searching each one again for every combination, at the
+    /// cost of keeping their solutions.
+    pub fn set_decompose(&mut self, decompose: bool) {
+        self.decompose = decompose;
     }
 
     pub fn problem(&self) -> &Problem<A, C> {
@@ -161,16 +685,109 @@ impl<A: Action, C: Constraint> Solver<A, C> {
     /// solution-returning method will return no solution, even if
     /// another solution (that doesn't contain the require actions)
     /// would otherewise exits.
-    pub fn require_action(&mut self, action: A) -> Result<(), String> {
-        match self.problem.require_row(action) {
-            Ok(_) => {
-                self.partial_solution.push(action);
-                Ok(())
-            },
-            Err(s) => {
-                Err(s)
+    ///
+    /// Returns an error, and leaves the solver as it was, if the
+    /// action doesn't exist, has already been required, or conflicts
+    /// with the actions already required. Requirements can be undone with `pop_requirement()`,
+    /// `unrequire_action()` or scopes (see `push_scope()`).
+    pub fn require_action(&mut self, action: A) -> Result<(), Error<A, C>> {
+        self.problem.require_row(action)?;
+        self.partial_solution.push(action);
+        Ok(())
+    }
+
+    /// Return the required actions, in the order they were required.
+    pub fn required_actions(&self) -> &[A] {
+        &self.partial_solution
+    }
+
+    /// Undo the last requirement, restoring the matrix as it was.
+    fn release_last(&mut self) -> Option<A> {
+        let action = self.partial_solution.pop()?;
+        self.problem.release_row(action);
+        Some(action)
+    }
+
+    /// Undo the most recent `require_action()`, returning the action
+    /// that was required, if any. Open scopes that required it lose
+    /// it.
+    pub fn pop_requirement(&mut self) -> Option<A> {
+        let action = self.release_last()?;
+        let n = self.partial_solution.len();
+        for mark in &mut self.scopes {
+            *mark = (*mark).min(n);
+        }
+        Some(action)
+    }
+
+    /// Stop requiring an action, returning false if it wasn't
+    /// required. The actions required after it are released and
+    /// required again, so this is cheapest for recent actions.
+    pub fn unrequire_action(&mut self, action: A) -> bool {
+        let i = match self.partial_solution.iter().position(|a| *a == action) {
+            Some(i) => i,
+            None => return false
+        };
+
+        let mut later = Vec::new();
+        while self.partial_solution.len() > i + 1 {
+            later.push(self.release_last().unwrap());
+        }
+        self.release_last();
+        for a in later.into_iter().rev() {
+            // They were compatible with more requirements than this.
+            let required = self.require_action(a);
+            assert!(required.is_ok(), "requirements stay compatible");
+        }
+
+        for mark in &mut self.scopes {
+            if *mark > i {
+                *mark -= 1;
             }
         }
+        true
+    }
+
+    /// Stop requiring any action, and close every scope.
+    pub fn clear_requirements(&mut self) {
+        while self.release_last().is_some() {}
+        self.scopes.clear();
+    }
+
+    /// Open a scope: `pop_scope()` undoes every requirement made
+    /// since. Scopes nest.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(self.partial_solution.len());
+    }
+
+    /// Close the innermost scope, releasing the actions required since
+    /// it was opened and returning them in the order they were
+    /// required. Returns None if there is no open scope.
+    pub fn pop_scope(&mut self) -> Option<Vec<A>> {
+        let mark = self.scopes.pop()?;
+        let mut released = Vec::new();
+        while self.partial_solution.len() > mark {
+            released.push(self.release_last().unwrap());
+        }
+        released.reverse();
+        Some(released)
+    }
+
+    /// Set limits on the work done by `first_solution()`,
+    /// `random_solution()` and iterating over solutions, after which
+    /// they give up. There are no limits by default.
+    pub fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    /// Call `observer` every `interval` nodes of the search with the
+    /// progress made, when looking for the first solution or iterating
+    /// over all of them. If the problem falls apart into independent
+    /// components, the progress is that of the component being
+    /// searched.
+    pub fn set_progress<F>(&mut self, interval: u64, observer: F)
+        where F: Fn(&Progress) + Send + Sync + 'static {
+        self.progress = Some(Observer { interval: interval.max(1), report: Arc::new(observer) });
     }
 
     /// Return a solution to the problem that includes any previously
@@ -181,57 +798,332 @@ impl<A: Action, C: Constraint> Solver<A, C> {
     /// that, for instance, the lexographically smallest action-set is
     /// returned. It is only guaranteed that, if at least one solution
     /// exists, a solution will be returned.
+    ///
+    /// If the search gives up on its limits, None is returned; use
+    /// `try_first_solution()` to tell the difference.
     pub fn first_solution(&self) -> Option<Vec<A>> {
+        self.try_first_solution().found()
+    }
+
+    /// Like `first_solution()`, but distinguish the problem having no
+    /// solution from the search giving up on its limits.
+    pub fn try_first_solution(&self) -> SearchResult<Vec<A>> {
+        self.first_solution_with_stats().0
+    }
+
+    /// Like `try_first_solution()`, also returning statistics on the
+    /// search.
+    pub fn first_solution_with_stats(&self) -> (SearchResult<Vec<A>>, SearchStats) {
+        let mut shuffle = self.shuffle.clone();
+        self.first_solution_within(&mut self.chooser.clone(), &mut shuffle)
+    }
+
+    /// Return a solution picked by a randomized search (see
+    /// `randomized()`), if one exists. Different seeds generally give
+    /// different solutions, and the same seed always gives the same
+    /// one.
+    pub fn random_solution(&self, seed: u64) -> Option<Vec<A>> {
+        let (mut chooser, shuffle) = random_search(seed);
+        self.first_solution_within(&mut chooser, &mut Some(shuffle)).0.found()
+    }
+
+    fn first_solution_within<H2>(&self, chooser: &mut H2, shuffle: &mut Option<Rng>) -> (SearchResult<Vec<A>>, SearchStats)
+        where H2: ColumnChooser<C> {
+        let mut m = self.problem.matrix.clone();
         let mut sol: Vec<A> = Vec::new();
-        if self.first_solution_aux(&mut sol){
-            Some(sol)
-        } else {
-            None
-        }
+        let mut budget = Budget::new(self.limits.clone());
+        budget.progress = self.progress.clone();
+        let result = match self.first_solution_aux(&mut m, chooser, shuffle, &mut budget, 0, &mut sol) {
+            Ok(true) => SearchResult::Found(sol),
+            Ok(false) => SearchResult::NoSolution,
+            Err(reason) => SearchResult::GaveUp(reason)
+        };
+        budget.stats.updates = m.updates() - self.problem.matrix.updates();
+        (result, budget.stats)
     }
 
-    fn first_solution_aux(&self, solution: &mut Vec<A>) -> bool {
-        let (_tc, action_nodes) = {
-            let constraint = self.problem.choose_column();
-            if let None = constraint {
+    fn first_solution_aux<H2>(&self, m: &mut Matrix, chooser: &mut H2, shuffle: &mut Option<Rng>,
+                              budget: &mut Budget, depth: usize, solution: &mut Vec<A>) -> Result<bool, StopReason>
+        where H2: ColumnChooser<C> {
+        let mut frame = {
+            let constraint = choose_column(m, &self.problem.constraints, chooser);
+            if constraint.is_none() {
+                budget.solution();
                 solution.extend_from_slice(&self.partial_solution);
-                return true;
+                return Ok(true);
             }
             
             let con = constraint.unwrap();
 
-            if con.borrow().get_count().unwrap() == 0 {
-                return false;
+            if m.degree(con) == 0 {
+                return Ok(false);
             }
+            budget.frame(depth, m.branches(con));
 
             // pick an action for the constraint to satisfy
-            (try_cover_column(&con), iter_col(&con))
+            new_frame(m, con, shuffle)
         };
 
         // Try that action, and return the solution to partial
-        // problem, if possible.
-        for action in action_nodes {
-            let a = self.problem.get_action(&action);
+        // problem, if possible. Giving up leaves the matrix as it is.
+        let mut found = false;
+        loop {
+            budget.visit()?;
+            let action = match frame.advance(m) {
+                Some(action) => action,
+                None => break
+            };
+            if let Some(action) = action {
+                solution.push(self.problem.get_action(m.row(action)));
+            }
+            budget.node(depth + 1);
+
+            if self.first_solution_aux(m, chooser, shuffle, budget, depth + 1, solution)? {
+                found = true;
+                break;
+            }
+
+            if action.is_some() {
+                solution.pop();
+            }
+        }
+
+        frame.finish(m);
+        Ok(found)
+    }
+
+    /// Return the number of solutions that include any previously
+    /// required actions, counting no further than `limit`, if given.
+    ///
+    /// This walks the same search as iterating over the solutions,
+    /// but doesn't build them. To check that a puzzle has a unique
+    /// solution, for instance, `count_solutions(Some(2)) == 1`.
+    ///
+    /// Independent components of the problem are counted separately,
+    /// and their counts multiplied; see `set_decompose()`.
+    pub fn count_solutions(&self, limit: Option<usize>) -> usize {
+        self.count_solutions_with_stats(limit).0
+    }
 
-            solution.push(a);
+    /// Like `count_solutions()`, also returning statistics on the
+    /// search. Solutions of independent components are counted once
+    /// each, not once per combination.
+    pub fn count_solutions_with_stats(&self, limit: Option<usize>) -> (usize, SearchStats) {
+        let mut m = self.problem.matrix.clone();
+        let mut counter = self.counter();
+        let count = counter.count_components(&mut m, limit.unwrap_or(usize::MAX));
+        let mut stats = counter.budget.stats;
+        stats.updates = m.updates() - self.problem.matrix.updates();
+        (count, stats)
+    }
+
+    pub(crate) fn counter(&self) -> Counter<'_, C, H> {
+        Counter { names: &self.problem.constraints, chooser: self.chooser.clone(), decompose: self.decompose,
+                  depth: 0, budget: Budget::default() }
+    }
 
-            let _cover = try_cover_row(&action);
-            let sol = self.first_solution_aux(solution);
+    /// Return a solution of minimum total cost (as set via
+    /// `Problem::set_action_cost()`) that includes any previously
+    /// required actions, along with its cost, if a solution exists.
+    ///
+    /// The search is a branch-and-bound over the same search tree as
+    /// `first_solution()`: any branch whose partial cost, plus a lower
+    /// bound on the cost of completing it, can't beat the cheapest
+    /// solution found so far is skipped. Total costs saturate at
+    /// `u64::MAX`.
+    pub fn min_cost_solution(&self) -> Option<(Vec<A>, u64)> {
+        let mut best = None;
+        let mut limit = Some(u64::MAX);
+        let mut m = self.problem.matrix.clone();
+        self.cost_aux(&mut m, &mut self.chooser.clone(), self.partial_cost(), &mut Vec::new(), &mut limit, &mut |sol, cost| {
+            best = Some((sol, cost));
+            // Only strictly cheaper solutions are of interest now.
+            cost.checked_sub(1)
+        });
+        best
+    }
+
+    /// Return every solution whose total cost is at most `bound`,
+    /// along with its cost.
+    pub fn solutions_within_cost(&self, bound: u64) -> Vec<(Vec<A>, u64)> {
+        let mut sols = Vec::new();
+        let mut limit = Some(bound);
+        let mut m = self.problem.matrix.clone();
+        self.cost_aux(&mut m, &mut self.chooser.clone(), self.partial_cost(), &mut Vec::new(), &mut limit, &mut |sol, cost| {
+            sols.push((sol, cost));
+            Some(bound)
+        });
+        sols
+    }
+
+    fn partial_cost(&self) -> u64 {
+        self.partial_solution.iter()
+            .map(|a| self.problem.action_cost(*a).unwrap())
+            .fold(0, u64::saturating_add)
+    }
 
-            if sol {
-                return true;
+    /// Search for solutions costing at most `limit`, calling
+    /// `on_solution` for each one found. `on_solution` returns the
+    /// new limit, or None to stop the search.
+    fn cost_aux<F>(&self, m: &mut Matrix, chooser: &mut H, cost: u64, solution: &mut Vec<A>, limit: &mut Option<u64>, on_solution: &mut F)
+        where F: FnMut(Vec<A>, u64) -> Option<u64> {
+        let max = match *limit {
+            Some(max) => max,
+            None => return
+        };
+        if cost.saturating_add(self.problem.cost_lower_bound(m)) > max {
+            return;
+        }
+
+        let mut frame = match choose_column(m, &self.problem.constraints, chooser) {
+            None => {
+                let mut sol = solution.clone();
+                sol.extend_from_slice(&self.partial_solution);
+                *limit = on_solution(sol, cost);
+                return;
+            },
+            Some(c) => {
+                if m.degree(c) == 0 {
+                    return;
+                }
+                FrameState::new(m, c)
             }
+        };
 
-            solution.pop();
+        while let Some(action) = frame.advance(m) {
+            match action {
+                Some(action) => {
+                    let r = m.row(action);
+                    solution.push(self.problem.get_action(r));
+                    self.cost_aux(m, chooser, cost.saturating_add(self.problem.get_cost(r)), solution, limit, on_solution);
+                    solution.pop();
+                },
+                None => self.cost_aux(m, chooser, cost, solution, limit, on_solution)
+            }
+
+            if limit.is_none() {
+                break;
+            }
+        }
+
+        frame.finish(m);
+    }
+}
+
+/// Return the column chooser and row order of a randomized search.
+fn random_search(seed: u64) -> (MrvRandom, Rng) {
+    let mut rng = Rng::new(seed);
+    (MrvRandom::new(rng.next_u64()), Rng::new(rng.next_u64()))
+}
+
+/// Start a frame on column `c`, shuffling its rows if there's a
+/// random number generator.
+fn new_frame(m: &mut Matrix, c: ColumnIndex, shuffle: &mut Option<Rng>) -> FrameState {
+    let mut frame = FrameState::new(m, c);
+    if let Some(ref mut rng) = *shuffle {
+        frame.shuffle(m, rng);
+    }
+    frame
+}
+
+/// A `Counter` counts the solutions below the current state of a
+/// matrix, without building them.
+pub(crate) struct Counter<'a, C: 'a, H> {
+    names: &'a [C],
+    chooser: H,
+    decompose: bool,
+    /// Number of actions selected below the starting matrix.
+    depth: usize,
+    /// Only used to gather statistics.
+    budget: Budget
+}
+
+impl<'a, C, H: ColumnChooser<C>> Counter<'a, C, H> {
+    /// Count the solutions, up to `limit`, multiplying the counts of
+    /// the independent components of the matrix. With `decompose`,
+    /// the search below is split again at every node.
+    pub(crate) fn count_components(&mut self, m: &mut Matrix, limit: usize) -> usize {
+        let comps = components(m);
+        if comps.len() <= 1 {
+            return self.count(m, limit);
+        }
+
+        // Make sure every component has a solution before counting
+        // any of them in full.
+        for i in 0..comps.len() {
+            if self.count_component(m, &comps, i, 1) == 0 {
+                return 0;
+            }
         }
 
-        false
+        // Every count is at least 1, so once a component reaches the
+        // limit, so does the product.
+        let mut product: usize = 1;
+        for i in 0..comps.len() {
+            let count = self.count_component(m, &comps, i, limit);
+            product = product.saturating_mul(count).min(limit);
+        }
+        product
+    }
+
+    /// Count the solutions of component `i` alone, by taking the
+    /// columns of the other components out of the header list for the
+    /// duration.
+    fn count_component(&mut self, m: &mut Matrix, comps: &[Vec<ColumnIndex>], i: usize, limit: usize) -> usize {
+        let others: Vec<ColumnIndex> = comps.iter().enumerate()
+            .filter(|&(j, _)| j != i)
+            .flat_map(|(_, cols)| cols.iter().cloned())
+            .collect();
+        for c in &others {
+            let h = m.header(*c);
+            m.remove_from_row(h);
+        }
+        let count = self.count(m, limit);
+        for c in others.iter().rev() {
+            let h = m.header(*c);
+            m.reinsert_into_row(h);
+        }
+        count
+    }
+
+    /// Count the solutions, up to `limit`.
+    pub(crate) fn count(&mut self, m: &mut Matrix, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+
+        let c = match choose_column(m, self.names, &mut self.chooser) {
+            None => {
+                self.budget.solution();
+                return 1;
+            },
+            Some(c) => c
+        };
+        if m.degree(c) == 0 {
+            return 0;
+        }
+
+        let mut count = 0;
+        self.budget.frame(self.depth, m.branches(c));
+        let mut frame = FrameState::new(m, c);
+        while count < limit && frame.advance(m).is_some() {
+            self.depth += 1;
+            self.budget.node(self.depth);
+            count += if self.decompose {
+                self.count_components(m, limit - count)
+            } else {
+                self.count(m, limit - count)
+            };
+            self.depth -= 1;
+        }
+        frame.finish(m);
+        count
     }
 }
 
-impl<A: Action, C: Constraint> IntoIterator for Solver<A, C> {
+impl<A: Action, C: Constraint, H: ColumnChooser<C> + Clone> IntoIterator for Solver<A, C, H> {
     type Item = Vec<A>;
-    type IntoIter = SolutionIterator<A, C>;
+    type IntoIter = SolutionIterator<A, C, H>;
 
     fn into_iter(self) -> Self::IntoIter {
         SolutionIterator::from_solver(self)
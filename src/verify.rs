@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use node::Color;
+use iter::iter_full_row;
+use problem::{Problem, Constraint, Action};
+
+/// A `Verification` reports everything wrong with a candidate
+/// solution, as returned by `Problem::verify()`. Constraints are
+/// listed in the order they were added to the problem, and actions in
+/// the order they appear in the candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification<A, C> {
+    /// Actions that aren't part of the problem.
+    pub unknown_actions: Vec<A>,
+    /// Actions listed more than once; they are only counted once
+    /// below.
+    pub duplicate_actions: Vec<A>,
+    /// Constraints satisfied by fewer actions than they need (none,
+    /// for an ordinary constraint), with the number that do satisfy
+    /// them.
+    pub uncovered: Vec<(C, usize)>,
+    /// Constraints satisfied by more actions than they allow (more
+    /// than one, for an ordinary or secondary constraint), with those
+    /// actions.
+    pub overcovered: Vec<(C, Vec<A>)>,
+    /// Constraints satisfied by actions of different colors, or by
+    /// colored and uncolored actions, with those actions.
+    pub color_conflicts: Vec<(C, Vec<A>)>
+}
+
+impl<A, C> Verification<A, C> {
+    /// Return true if the candidate is a solution of the problem.
+    pub fn is_valid(&self) -> bool {
+        self.unknown_actions.is_empty() && self.duplicate_actions.is_empty() && self.uncovered.is_empty()
+            && self.overcovered.is_empty() && self.color_conflicts.is_empty()
+    }
+}
+
+impl<A: fmt::Debug, C: fmt::Debug> fmt::Display for Verification<A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "valid solution");
+        }
+        let mut problems = Vec::new();
+        for a in &self.unknown_actions {
+            problems.push(format!("unknown action {:?}", a));
+        }
+        for a in &self.duplicate_actions {
+            problems.push(format!("duplicate action {:?}", a));
+        }
+        for (c, n) in &self.uncovered {
+            problems.push(format!("constraint {:?} satisfied by only {} action(s)", c, n));
+        }
+        for (c, actions) in &self.overcovered {
+            problems.push(format!("constraint {:?} satisfied by too many actions: {:?}", c, actions));
+        }
+        for (c, actions) in &self.color_conflicts {
+            problems.push(format!("constraint {:?} has conflicting colors: {:?}", c, actions));
+        }
+        write!(f, "{}", problems.join("; "))
+    }
+}
+
+impl<A: Action, C: Constraint> Problem<A, C> {
+    /// Check a candidate solution against the definition of the
+    /// problem, independently of any search: every constraint must be
+    /// satisfied by a number of the actions within its bounds, and
+    /// colored constraints by actions of a single color. Rows
+    /// required via `require_row()` are not taken into account.
+    pub fn verify(&self, actions: &[A]) -> Verification<A, C> {
+        let m = &self.matrix;
+        let mut report = Verification {
+            unknown_actions: Vec::new(),
+            duplicate_actions: Vec::new(),
+            uncovered: Vec::new(),
+            overcovered: Vec::new(),
+            color_conflicts: Vec::new()
+        };
+
+        // The actions counted toward each constraint, and all those
+        // touching it with their colors.
+        let mut counted: Vec<Vec<A>> = vec![Vec::new(); m.num_columns()];
+        let mut touching: Vec<Vec<(A, Option<Color>)>> = vec![Vec::new(); m.num_columns()];
+        let mut seen = HashSet::new();
+        for &a in actions {
+            if !seen.insert(a) {
+                if !report.duplicate_actions.contains(&a) {
+                    report.duplicate_actions.push(a);
+                }
+                continue;
+            }
+            let r = match self.get_action_row(a) {
+                Some(r) => r,
+                None => {
+                    report.unknown_actions.push(a);
+                    continue;
+                }
+            };
+            for n in iter_full_row(m, m.row_start(r)) {
+                let c = m.column(n);
+                if m.color(n).is_none() {
+                    counted[c].push(a);
+                }
+                touching[c].push((a, m.color(n)));
+            }
+        }
+
+        for c in 0..m.num_columns() {
+            let b = m.bounds(c);
+            let constraint = &self.constraints[c];
+            if counted[c].len() < b.lo {
+                report.uncovered.push((constraint.clone(), counted[c].len()));
+            }
+            if counted[c].len() > b.hi {
+                report.overcovered.push((constraint.clone(), counted[c].clone()));
+            }
+
+            let colors: HashSet<Option<Color>> = touching[c].iter().map(|t| t.1).collect();
+            if colors.len() > 1 && colors.iter().any(|color| color.is_some()) {
+                report.color_conflicts.push((constraint.clone(), touching[c].iter().map(|t| t.0).collect()));
+            }
+        }
+        report
+    }
+}
@@ -0,0 +1,128 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver, SolutionIterator};
+use common::{Staff, staffing, sorted};
+
+#[test]
+fn staffing_count() {
+    // Every split of 4 people into two shifts, except the two that
+    // leave a shift empty.
+    assert_eq!(Solver::new(staffing(4, 2, 1, 3)).into_iter().count(), 14);
+
+    // Only even splits.
+    assert_eq!(Solver::new(staffing(4, 2, 2, 3)).into_iter().count(), 6);
+    assert_eq!(Solver::new(staffing(4, 2, 2, 2)).into_iter().count(), 6);
+
+    // No way to staff three shifts with two people each.
+    assert!(Solver::new(staffing(4, 3, 2, 3)).first_solution().is_none());
+}
+
+#[test]
+fn no_duplicate_solutions() {
+    let sols = sorted(Solver::new(staffing(5, 3, 1, 3)).into_iter());
+    let mut dedup = sols.clone();
+    dedup.dedup();
+    assert_eq!(sols, dedup);
+
+    // 3^5 assignments, minus those leaving some shift empty.
+    assert_eq!(sols.len(), 243 - 3 * 32 + 3);
+}
+
+#[test]
+fn optional_bounded_constraint() {
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&"x", 0, 2);
+    p.add_action(0, &["a", "x"]).unwrap();
+    p.add_action(1, &["b", "x"]).unwrap();
+    p.add_action(2, &["c", "x"]).unwrap();
+    p.add_action(3, &["a", "b", "c"]).unwrap();
+
+    assert!(p.is_secondary(&"x"));
+    assert_eq!(p.constraint_bounds(&"x"), Some((0, 2)));
+
+    // {0, 1, 2} would use "x" three times.
+    assert_eq!(Solver::new(p).into_iter().count(), 1);
+}
+
+#[test]
+fn require_bounded() {
+    let mut solver = Solver::new(staffing(4, 2, 1, 3));
+    assert!(solver.require_action((0, 0)).is_ok());
+    assert!(solver.require_action((1, 0)).is_ok());
+    assert!(solver.require_action((2, 0)).is_ok());
+    assert!(solver.first_solution().is_some());
+    assert!(solver.require_action((3, 0)).is_err());
+
+    let sols = sorted(solver.into_iter());
+    assert_eq!(sols, vec![vec![(0, 0), (1, 0), (2, 0), (3, 1)]]);
+}
+
+/// Two shifts of one or two people, and three people working at most
+/// one shift each: the shifts are the only primary constraints.
+fn roster() -> Problem<(usize, usize), Staff> {
+    let mut p = Problem::new();
+    for s in 0..2 {
+        p.add_bounded_constraint(&Staff::Shift(s), 1, 2);
+    }
+    for i in 0..3 {
+        p.add_secondary_constraint(&Staff::Person(i));
+    }
+    for i in 0..3 {
+        for s in 0..2 {
+            p.add_action((i, s), &[Staff::Person(i), Staff::Shift(s)]).unwrap();
+        }
+    }
+    p
+}
+
+/// Every set of actions of `roster()` that verifies, sorted.
+fn roster_by_brute_force() -> Vec<Vec<(usize, usize)>> {
+    let p = roster();
+    let actions: Vec<(usize, usize)> = (0..3).flat_map(|i| (0..2).map(move |s| (i, s))).collect();
+    let mut sols: Vec<Vec<(usize, usize)>> = (0..1 << actions.len())
+        .map(|bits: usize| actions.iter().enumerate().filter(|&(k, _)| bits & (1 << k) != 0).map(|(_, a)| *a).collect())
+        .filter(|sol: &Vec<(usize, usize)>| p.verify(sol).is_valid())
+        .collect();
+    sols.sort();
+    sols
+}
+
+#[test]
+fn bounded_only_primary() {
+    let all = roster_by_brute_force();
+    // One person on each shift, with or without a second on either.
+    assert_eq!(all.len(), 12);
+    assert!(all.contains(&vec![(0, 0), (1, 0), (2, 1)]));
+
+    let solver = Solver::new(roster());
+    assert_eq!(solver.count_solutions(None), 12);
+    assert_eq!(sorted(solver.into_iter()), all);
+
+    let mut random: Vec<Vec<(usize, usize)>> = Solver::new(roster()).randomized(7).into_iter()
+        .map(|mut s| { s.sort(); s })
+        .collect();
+    random.sort();
+    assert_eq!(random, all);
+
+    assert_eq!(Solver::new(roster()).sampler(1).num_solutions(), 12);
+
+    let mut parallel: Vec<Vec<(usize, usize)>> = Solver::new(roster()).par_solutions(2)
+        .map(|mut s| { s.sort(); s })
+        .collect();
+    parallel.sort();
+    assert_eq!(parallel, all);
+}
+
+#[test]
+fn bounded_only_primary_resume() {
+    let all: Vec<_> = Solver::new(roster()).into_iter().collect();
+    let mut iter = Solver::new(roster()).into_iter();
+    for i in 0..all.len() + 1 {
+        let rest: Vec<_> = SolutionIterator::resume(Solver::new(roster()), &iter.checkpoint()).unwrap().collect();
+        assert_eq!(&rest[..], &all[i..]);
+        assert_eq!(iter.next(), all.get(i).cloned());
+    }
+}
@@ -0,0 +1,108 @@
+extern crate exact_cover;
+
+use std::collections::HashSet;
+
+use exact_cover::{Problem, Solver, SolutionIterator, Checkpoint, Error, SearchLimits};
+
+fn queens_problem(n: usize) -> Problem<(usize, usize, usize), (usize, u8, usize)> {
+    let mut p = Problem::new();
+    add_queens(&mut p, n, 0);
+    p
+}
+
+/// Add an n-queens problem, tagged with `t`.
+fn add_queens(p: &mut Problem<(usize, usize, usize), (usize, u8, usize)>, n: usize, t: usize) {
+    for i in 0..(2*n - 1) {
+        p.add_secondary_constraint(&(t, 2, i));
+        p.add_secondary_constraint(&(t, 3, i));
+    }
+    for r in 0..n {
+        for c in 0..n {
+            p.add_action((t, r, c), &[(t, 0, r), (t, 1, c), (t, 2, r + c), (t, 3, n - 1 + r - c)]).unwrap();
+        }
+    }
+}
+
+/// Check that resuming at every position of the iteration produces
+/// the remaining solutions in the same order.
+fn check_every_position<F>(solver: F)
+    where F: Fn() -> Solver<(usize, usize, usize), (usize, u8, usize)> {
+    let all: Vec<_> = solver().into_iter().collect();
+    let mut iter = solver().into_iter();
+    for i in 0..all.len() + 1 {
+        let text = iter.checkpoint().to_string();
+        let checkpoint: Checkpoint = text.parse().unwrap();
+        let rest: Vec<_> = SolutionIterator::resume(solver(), &checkpoint).unwrap().collect();
+        assert_eq!(&rest[..], &all[i..]);
+        assert_eq!(iter.next(), all.get(i).cloned());
+    }
+}
+
+#[test]
+fn resume_everywhere() {
+    check_every_position(|| Solver::new(queens_problem(6)));
+}
+
+#[test]
+fn resume_components() {
+    check_every_position(|| {
+        let mut p = queens_problem(4);
+        add_queens(&mut p, 5, 1);
+        add_queens(&mut p, 6, 2);
+        let mut solver = Solver::new(p);
+        solver.set_decompose(true);
+        solver
+    });
+}
+
+#[test]
+fn resume_with_required() {
+    check_every_position(|| {
+        let mut solver = Solver::new(queens_problem(8));
+        solver.require_action((0, 0, 2)).unwrap();
+        solver
+    });
+}
+
+#[test]
+fn resume_randomized() {
+    let solver = || Solver::new(queens_problem(8)).randomized(3);
+    let mut iter = solver().into_iter();
+    let first: Vec<_> = iter.by_ref().take(40).collect();
+    let rest: Vec<_> = SolutionIterator::resume(solver(), &iter.checkpoint()).unwrap().collect();
+
+    let all: HashSet<_> = first.iter().chain(rest.iter()).cloned().collect();
+    assert_eq!(first.len() + rest.len(), 92);
+    assert_eq!(all.len(), 92);
+}
+
+#[test]
+fn resume_after_giving_up() {
+    let mut solver = Solver::new(queens_problem(8));
+    solver.set_limits(SearchLimits::new().max_nodes(500));
+    let mut iter = solver.into_iter();
+    let mut found: Vec<_> = iter.by_ref().collect();
+    assert!(iter.stopped().is_some());
+
+    let checkpoint = Checkpoint::from_words(iter.checkpoint().words().to_vec());
+    found.extend(SolutionIterator::resume(Solver::new(queens_problem(8)), &checkpoint).unwrap());
+    let all: Vec<_> = Solver::new(queens_problem(8)).into_iter().collect();
+    assert_eq!(found, all);
+}
+
+#[test]
+fn invalid_checkpoints() {
+    let mut iter = Solver::new(queens_problem(6)).into_iter();
+    iter.next();
+    let checkpoint = iter.checkpoint();
+    assert!(SolutionIterator::resume(Solver::new(queens_problem(6)), &checkpoint).is_ok());
+
+    assert_eq!(SolutionIterator::resume(Solver::new(queens_problem(7)), &checkpoint).err(),
+               Some(Error::InvalidCheckpoint));
+
+    let mut words = checkpoint.words().to_vec();
+    words.pop();
+    assert!(SolutionIterator::resume(Solver::new(queens_problem(6)), &Checkpoint::from_words(words)).is_err());
+    assert!(SolutionIterator::resume(Solver::new(queens_problem(6)), &Checkpoint::from_words(vec![])).is_err());
+    assert!("1 2 x".parse::<Checkpoint>().is_err());
+}
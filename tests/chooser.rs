@@ -0,0 +1,101 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver, ColumnChooser};
+use exact_cover::chooser::{Columns, Column, Mrv, FirstColumn, MrvRandom, MrvPriority};
+use exact_cover::instances::sudoku::{sudoku_solver, sudoku_problem, solution_as_matrix, SudokuConstraint};
+use common::{QueenConstraint, queens_problem, sorted};
+
+/// Choose the constraint with the most options, checking the counts
+/// reported along the way.
+#[derive(Clone)]
+struct MostOptions;
+
+impl ColumnChooser<QueenConstraint> for MostOptions {
+    fn choose<'a>(&mut self, columns: Columns<'a, QueenConstraint>) -> Column<'a, QueenConstraint> {
+        for c in columns.iter() {
+            assert_eq!(c.need(), 1);
+            assert_eq!(c.degree(), c.len());
+            match *c.constraint() {
+                QueenConstraint::Row(_) | QueenConstraint::Col(_) => (),
+                _ => panic!("secondary constraints are never chosen")
+            }
+        }
+        columns.iter().max_by_key(|c| c.len()).unwrap()
+    }
+}
+
+#[test]
+fn choosers_agree() {
+    let expected = sorted(Solver::new(queens_problem(6)).into_iter());
+    assert_eq!(expected.len(), 4);
+
+    assert_eq!(sorted(Solver::with_chooser(queens_problem(6), Mrv).into_iter()), expected);
+    assert_eq!(sorted(Solver::with_chooser(queens_problem(6), FirstColumn).into_iter()), expected);
+    assert_eq!(sorted(Solver::with_chooser(queens_problem(6), MrvRandom::new(7)).into_iter()), expected);
+    assert_eq!(sorted(Solver::with_chooser(queens_problem(6), MostOptions).into_iter()), expected);
+
+    let by_kind = MrvPriority::new(|c: &QueenConstraint| match *c {
+        QueenConstraint::Col(_) => 0,
+        _ => 1
+    });
+    let solver = Solver::with_chooser(queens_problem(6), by_kind);
+    assert_eq!(solver.count_solutions(None), 4);
+    assert!(solver.first_solution().is_some());
+    assert_eq!(sorted(solver.par_solutions(2)), expected);
+}
+
+#[test]
+fn first_column_order() {
+    // The constraint branched on first comes first in the solution.
+    let mut p = Problem::new();
+    p.add_constraint(&"b");
+    p.add_constraint(&"a");
+    p.add_action(1, &["a"]).unwrap();
+    p.add_action(2, &["a"]).unwrap();
+    p.add_action(3, &["b"]).unwrap();
+    p.add_action(4, &["b"]).unwrap();
+    p.add_action(5, &["b"]).unwrap();
+
+    let sol = Solver::with_chooser(p.clone(), FirstColumn).first_solution().unwrap();
+    assert_eq!(sol, vec![3, 1]);
+    let sol = Solver::new(p).first_solution().unwrap();
+    assert_eq!(sol, vec![1, 3]);
+}
+
+#[test]
+fn random_tie_breaking() {
+    let sols = |seed| Solver::with_chooser(queens_problem(8), MrvRandom::new(seed)).into_iter().collect::<Vec<_>>();
+    assert_eq!(sols(3), sols(3));
+    assert_eq!(sorted(sols(3).into_iter()), sorted(sols(4).into_iter()));
+    assert_eq!(sols(3).len(), 92);
+}
+
+#[test]
+fn sudoku_priority() {
+    let existence_first = MrvPriority::new(|c: &SudokuConstraint| match *c {
+        SudokuConstraint::Existence(_, _) => 0,
+        SudokuConstraint::Uniqueness(_, _) => 1
+    });
+
+    let p = [0, 0, 3, 0, 2, 0, 6, 0, 0,
+             9, 0, 0, 3, 0, 5, 0, 0, 1,
+             0, 0, 1, 8, 0, 6, 4, 0, 0,
+             0, 0, 8, 1, 0, 2, 9, 0, 0,
+             7, 0, 0, 0, 0, 0, 0, 0, 8,
+             0, 0, 6, 7, 0, 8, 2, 0, 0,
+             0, 0, 2, 6, 0, 9, 5, 0, 0,
+             8, 0, 0, 2, 0, 3, 0, 0, 9,
+             0, 0, 5, 0, 1, 0, 3, 0, 0];
+    let solver = sudoku_solver(&p).unwrap();
+    let expected = solver.first_solution();
+    let solver = solver.use_chooser(existence_first.clone());
+    assert_eq!(solver.count_solutions(None), 1);
+    assert_eq!(solution_as_matrix(9, &solver.first_solution().unwrap()),
+               solution_as_matrix(9, &expected.unwrap()));
+
+    let empty = Solver::with_chooser(sudoku_problem(4).unwrap(), existence_first);
+    assert_eq!(empty.count_solutions(None), 288);
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const WIKIPEDIA: &str = "A: 1 4 7\nB: 1 4\nC: 4 5 7\nD: 3 5 6\nE: 2 3 6 7\nF: 2 7\n";
+
+const KNUTH: &str = "| Knuth's example\na b c d e f g\nc e\na d g\nb c f\na d f\nb g\nd e g\n";
+
+/// Run the binary on `input`, returning its exit code and output.
+fn run(args: &[&str], input: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_exact-cover"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // The binary may exit on bad arguments without reading its input.
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn simple_format() {
+    assert_eq!(run(&["first"], WIKIPEDIA), (0, "B\nD\nF\n\n".to_string()));
+    assert_eq!(run(&["count"], WIKIPEDIA), (0, "1\n".to_string()));
+    assert_eq!(run(&["--require", "A", "first"], WIKIPEDIA), (1, String::new()));
+    assert_eq!(run(&["--require", "A", "count", "-"], WIKIPEDIA), (0, "0\n".to_string()));
+}
+
+#[test]
+fn dlx_format() {
+    assert_eq!(run(&["all"], KNUTH), (0, "a d f\nb g\nc e\n\n".to_string()));
+    assert_eq!(run(&["--require", "b g", "count"], KNUTH), (0, "1\n".to_string()));
+    assert_eq!(run(&["--require", "0", "count"], KNUTH), (0, "1\n".to_string()));
+    assert_eq!(run(&["--require", "1", "count"], KNUTH), (0, "0\n".to_string()));
+    assert_eq!(run(&["--format", "simple", "count"], KNUTH).0, 2);
+}
+
+#[test]
+fn all_and_limits() {
+    let input = "x: 1\ny: 1\nz: 1\n";
+    assert_eq!(run(&["all"], input), (0, "x\n\ny\n\nz\n\n".to_string()));
+    assert_eq!(run(&["--limit", "2", "all"], input), (0, "x\n\ny\n\n".to_string()));
+    assert_eq!(run(&["--limit", "2", "count"], input), (0, "2\n".to_string()));
+}
+
+#[test]
+fn random() {
+    let input = "x: 1\ny: 1\nz: 1\n";
+    let (code, first) = run(&["--seed", "7", "random"], input);
+    assert_eq!(code, 0);
+    assert_eq!(run(&["random", "--seed", "7"], input).1, first);
+    assert!(["x\n\n", "y\n\n", "z\n\n"].contains(&first.as_str()));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(run(&[], WIKIPEDIA).0, 2);
+    assert_eq!(run(&["solve"], WIKIPEDIA).0, 2);
+    assert_eq!(run(&["--require", "G", "first"], WIKIPEDIA).0, 2);
+    assert_eq!(run(&["first"], "A: 1\nB 2\n").0, 2);
+    assert_eq!(run(&["--help"], "").0, 0);
+}
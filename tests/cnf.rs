@@ -0,0 +1,177 @@
+extern crate exact_cover;
+
+use std::collections::HashSet;
+
+use exact_cover::{Problem, Solver};
+use exact_cover::formats::cnf::{Cnf, Encoding, Lit, parse_model};
+
+const ENCODINGS: [Encoding; 3] = [Encoding::Pairwise, Encoding::Sequential, Encoding::Commander];
+
+fn value(assignment: &[Option<bool>], l: Lit) -> Option<bool> {
+    assignment[l.unsigned_abs() as usize].map(|v| v == (l > 0))
+}
+
+/// A tiny backtracking SAT solver.
+fn satisfiable(clauses: &[Vec<Lit>], assignment: &mut Vec<Option<bool>>) -> bool {
+    let mut branch = None;
+    for clause in clauses {
+        if clause.iter().any(|&l| value(assignment, l) == Some(true)) {
+            continue;
+        }
+        match clause.iter().find(|&&l| value(assignment, l).is_none()) {
+            None => return false,
+            Some(&l) => branch = branch.or(Some(l.unsigned_abs() as usize))
+        }
+    }
+    let v = match branch {
+        None => return true,
+        Some(v) => v
+    };
+    for b in [true, false] {
+        assignment[v] = Some(b);
+        if satisfiable(clauses, assignment) {
+            assignment[v] = None;
+            return true;
+        }
+    }
+    assignment[v] = None;
+    false
+}
+
+/// Return the solutions of the formula, as sets of actions: every
+/// assignment of the action variables that extends to a model.
+fn models<A: Copy + Eq + std::hash::Hash + Ord>(cnf: &Cnf<A>, num_actions: usize) -> HashSet<Vec<A>> {
+    let mut found = HashSet::new();
+    for bits in 0..(1u32 << num_actions) {
+        let mut assignment = vec![None; cnf.num_vars() + 1];
+        let mut model = Vec::new();
+        for i in 0..num_actions {
+            let b = bits & (1 << i) != 0;
+            assignment[i + 1] = Some(b);
+            model.push(if b { i as Lit + 1 } else { -(i as Lit) - 1 });
+        }
+        if satisfiable(cnf.clauses(), &mut assignment) {
+            let mut sol = cnf.solution(&model);
+            sol.sort();
+            found.insert(sol);
+        }
+    }
+    found
+}
+
+fn check<C: Clone + Eq + std::hash::Hash>(solver: Solver<u32, C>) {
+    let num_actions = solver.problem().num_actions();
+    let expected: HashSet<Vec<u32>> = solver.clone().into_iter()
+        .map(|mut s| { s.sort(); s })
+        .collect();
+    for &encoding in &ENCODINGS {
+        assert_eq!(models(&Cnf::from_solver(&solver, encoding), num_actions), expected, "{:?}", encoding);
+    }
+}
+
+#[test]
+fn knuth_example() {
+    let mut p = Problem::new();
+    for (i, items) in ["ce", "adg", "bcf", "adf", "bg", "deg"].iter().enumerate() {
+        p.add_action(i as u32, &items.chars().collect::<Vec<_>>()).unwrap();
+    }
+    check(Solver::new(p));
+}
+
+#[test]
+fn long_columns() {
+    // Choosing one of 10 actions for 'a', with other constraints
+    // pruning some of them.
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&'s');
+    for i in 0..10 {
+        let mut items = vec!['a'];
+        if i % 3 == 0 {
+            items.push('s');
+        }
+        p.add_action(i, &items).unwrap();
+    }
+    p.add_action(10, &['b']).unwrap();
+    p.add_action(11, &['b', 's']).unwrap();
+    check(Solver::new(p));
+}
+
+#[test]
+fn bounds_and_colors() {
+    // Assign 4 people to 2 shifts of 1 to 3 people, where people 0
+    // and 1 wear a color on shift 0, and the others none.
+    let mut p = Problem::new();
+    for s in 0..2 {
+        p.add_bounded_constraint(&(0, s), 1, 3);
+    }
+    p.add_secondary_constraint(&(1, 0));
+    for i in 0..4 {
+        for s in 0..2 {
+            let a = (4 * i + s) as u32;
+            if s == 0 && i < 2 {
+                p.add_colored_action(a, &[(2, i), (0, s)], &[((1, 0), i)]).unwrap();
+            } else if s == 0 && i == 2 {
+                p.add_action(a, &[(2, i), (0, s), (1, 0)]).unwrap();
+            } else {
+                p.add_action(a, &[(2, i), (0, s)]).unwrap();
+            }
+        }
+    }
+    check(Solver::new(p));
+}
+
+#[test]
+fn required_actions() {
+    let mut p = Problem::new();
+    for i in 0..4 {
+        p.add_action(i, &[i % 2, 2 + i / 2]).unwrap();
+    }
+    p.add_action(4, &[0, 1, 2, 3]).unwrap();
+    let mut solver = Solver::new(p);
+    check(solver.clone());
+    solver.require_action(4).unwrap();
+    check(solver);
+}
+
+#[test]
+fn secondary_only_actions() {
+    // Actions 2 and 3 satisfy no primary constraint, so the solver
+    // never picks them unless they are required.
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&'s');
+    p.add_secondary_constraint(&'t');
+    p.add_action(0, &['a', 's']).unwrap();
+    p.add_action(1, &['a']).unwrap();
+    p.add_action(2, &['t']).unwrap();
+    p.add_colored_action(3, &[], &[('s', 1)]).unwrap();
+    let mut solver = Solver::new(p);
+    check(solver.clone());
+    assert_eq!(solver.clone().into_iter().count(), 2);
+
+    solver.require_action(2).unwrap();
+    check(solver);
+}
+
+#[test]
+fn unsatisfiable_bounds() {
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&0, 3, 3);
+    p.add_action(0, &[0]).unwrap();
+    p.add_action(1, &[0]).unwrap();
+    check(Solver::new(p));
+}
+
+#[test]
+fn dimacs() {
+    let mut p = Problem::new();
+    p.add_action("x", &[0, 1]).unwrap();
+    p.add_action("y", &[1]).unwrap();
+    let cnf = Cnf::from_problem(&p, Encoding::Pairwise);
+    assert_eq!(cnf.to_dimacs(), "p cnf 2 3\n1 0\n1 2 0\n-1 -2 0\n");
+    assert_eq!(cnf.variable("y"), Some(2));
+
+    let output = "c a comment\ns SATISFIABLE\nv 1 -2\nv 0\n";
+    assert_eq!(parse_model(output), vec![1, -2]);
+    assert_eq!(cnf.solution(&parse_model(output)), vec!["x"]);
+    assert_eq!(parse_model("SAT\n-1 2 0\n"), vec![-1, 2]);
+}
@@ -0,0 +1,123 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver, Error};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Square {
+    Across(usize),
+    Down(usize),
+    Cell(usize, usize),
+    Word(usize)
+}
+
+const WORDS: [&str; 6] = ["at", "ta", "to", "ot", "tt", "oa"];
+
+/// Fill a 2x2 grid with distinct words reading across and down.
+fn word_square() -> Problem<(Square, usize), Square> {
+    let mut p = Problem::new();
+
+    // Each word may be used at most once, but needn't be used at all.
+    for w in 0..WORDS.len() {
+        p.add_secondary_constraint(&Square::Word(w));
+    }
+
+    for (w, word) in WORDS.iter().enumerate() {
+        let letters: Vec<usize> = word.chars().map(|c| c as usize).collect();
+        for i in 0..2 {
+            p.add_colored_action((Square::Across(i), w), &[Square::Across(i), Square::Word(w)],
+                                 &[(Square::Cell(i, 0), letters[0]), (Square::Cell(i, 1), letters[1])]).unwrap();
+            p.add_colored_action((Square::Down(i), w), &[Square::Down(i), Square::Word(w)],
+                                 &[(Square::Cell(0, i), letters[0]), (Square::Cell(1, i), letters[1])]).unwrap();
+        }
+    }
+    p
+}
+
+fn brute_force_count() -> usize {
+    let mut count = 0;
+    for (a0, w0) in WORDS.iter().enumerate() {
+        for (a1, w1) in WORDS.iter().enumerate() {
+            let grid: Vec<&[u8]> = vec![w0.as_bytes(), w1.as_bytes()];
+            let d0 = [grid[0][0], grid[1][0]];
+            let d1 = [grid[0][1], grid[1][1]];
+            let d0 = WORDS.iter().position(|w| w.as_bytes() == d0);
+            let d1 = WORDS.iter().position(|w| w.as_bytes() == d1);
+            if let (Some(d0), Some(d1)) = (d0, d1) {
+                let mut used = vec![a0, a1, d0, d1];
+                used.sort();
+                used.dedup();
+                if used.len() == 4 {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn word_square_count() {
+    let p = word_square();
+    assert!(p.is_secondary(&Square::Cell(0, 0)));
+
+    let count = Solver::new(p).into_iter().count();
+    assert!(count > 0);
+    assert_eq!(count, brute_force_count());
+}
+
+#[test]
+fn colors_must_agree() {
+    let mut p = Problem::new();
+    p.add_colored_action(0, &["a"], &[("x", 1)]).unwrap();
+    p.add_colored_action(1, &["b"], &[("x", 1)]).unwrap();
+    p.add_colored_action(2, &["b"], &[("x", 2)]).unwrap();
+    p.add_action(3, &["b", "x"]).unwrap();
+
+    let mut sols: Vec<Vec<usize>> = Solver::new(p).into_iter().map(|mut s| { s.sort(); s }).collect();
+    sols.sort();
+    assert_eq!(sols, vec![vec![0, 1]]);
+}
+
+#[test]
+fn require_colored() {
+    let mut p = Problem::new();
+    p.add_colored_action(0, &["a"], &[("x", 1)]).unwrap();
+    p.add_colored_action(1, &["b"], &[("x", 1)]).unwrap();
+    p.add_colored_action(2, &["b"], &[("x", 2)]).unwrap();
+
+    let mut solver = Solver::new(p);
+    assert!(solver.require_action(0).is_ok());
+    assert!(solver.require_action(2).is_err());
+    assert!(solver.require_action(1).is_ok());
+    assert_eq!(solver.first_solution().map(|s| s.len()), Some(2));
+}
+
+#[test]
+fn require_colored_twice() {
+    // An action with nothing but colors never conflicts with itself.
+    let mut p = Problem::new();
+    p.add_colored_action(0, &[], &[("x", 1)]).unwrap();
+    p.add_colored_action(1, &["a"], &[("x", 1)]).unwrap();
+
+    let mut solver = Solver::new(p);
+    assert!(solver.require_action(0).is_ok());
+    assert_eq!(solver.require_action(0), Err(Error::AlreadyRequired(0)));
+    assert_eq!(solver.pop_requirement(), Some(0));
+    assert_eq!(solver.first_solution(), Some(vec![1]));
+}
+
+#[test]
+fn colored_primary() {
+    let mut p = Problem::new();
+    p.add_action(0, &["a"]).unwrap();
+    assert_eq!(p.add_colored_action(1, &["b"], &[("a", 1)]),
+               Err(Error::ColoredPrimary { action: 1, constraint: "a" }));
+    assert_eq!(p.add_colored_action(2, &["x"], &[("x", 1)]),
+               Err(Error::ColoredPrimary { action: 2, constraint: "x" }));
+
+    // Nothing was added along the way.
+    assert_eq!(p.num_constraints(), 1);
+    assert_eq!(p.num_actions(), 1);
+    assert!(p.add_colored_action(1, &["b"], &[("x", 1)]).is_ok());
+}
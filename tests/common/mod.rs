@@ -0,0 +1,59 @@
+//! Problems and helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use exact_cover::Problem;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum QueenConstraint {
+    Row(usize),
+    Col(usize),
+    Diag(usize),
+    AntiDiag(usize)
+}
+
+/// Place `n` queens on an `n` x `n` board, none attacking another.
+pub fn queens_problem(n: usize) -> Problem<(usize, usize), QueenConstraint> {
+    let mut p = Problem::new();
+    for i in 0..(2*n - 1) {
+        p.add_secondary_constraint(&QueenConstraint::Diag(i));
+        p.add_secondary_constraint(&QueenConstraint::AntiDiag(i));
+    }
+    for r in 0..n {
+        for c in 0..n {
+            p.add_action((r, c), &[QueenConstraint::Row(r), QueenConstraint::Col(c),
+                                   QueenConstraint::Diag(r + c),
+                                   QueenConstraint::AntiDiag(n - 1 + r - c)]).unwrap();
+        }
+    }
+    p
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Staff {
+    Person(usize),
+    Shift(usize)
+}
+
+/// Every person works exactly one shift, and every shift has between
+/// `lo` and `hi` people.
+pub fn staffing(people: usize, shifts: usize, lo: usize, hi: usize) -> Problem<(usize, usize), Staff> {
+    let mut p = Problem::new();
+    for s in 0..shifts {
+        p.add_bounded_constraint(&Staff::Shift(s), lo, hi);
+    }
+    for i in 0..people {
+        for s in 0..shifts {
+            p.add_action((i, s), &[Staff::Person(i), Staff::Shift(s)]).unwrap();
+        }
+    }
+    p
+}
+
+/// Sort each solution, then the solutions, so that sets of solutions
+/// found in different orders compare equal.
+pub fn sorted<A: Ord, I: Iterator<Item = Vec<A>>>(sols: I) -> Vec<Vec<A>> {
+    let mut sols: Vec<Vec<A>> = sols.map(|mut s| { s.sort(); s }).collect();
+    sols.sort();
+    sols
+}
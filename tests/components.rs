@@ -0,0 +1,162 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver};
+use common::{sorted};
+
+/// Domino tilings of a `rows` x `cols` board.
+fn dominoes(rows: usize, cols: usize) -> Problem<(usize, usize, bool), (usize, usize)> {
+    let mut p = Problem::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            if c + 1 < cols {
+                p.add_action((r, c, true), &[(r, c), (r, c + 1)]).unwrap();
+            }
+            if r + 1 < rows {
+                p.add_action((r, c, false), &[(r, c), (r + 1, c)]).unwrap();
+            }
+        }
+    }
+    p
+}
+
+/// Two copies of the same small problem side by side, tagged by
+/// `side`.
+fn two_sides() -> Problem<(usize, &'static str), (usize, usize)> {
+    let mut p = Problem::new();
+    for side in 0..2 {
+        p.add_action((side, "01"), &[(side, 0), (side, 1)]).unwrap();
+        p.add_action((side, "12"), &[(side, 1), (side, 2)]).unwrap();
+        p.add_action((side, "0"), &[(side, 0)]).unwrap();
+        p.add_action((side, "2"), &[(side, 2)]).unwrap();
+    }
+    p
+}
+
+#[test]
+fn find_components() {
+    let p = two_sides();
+    let comps = p.components();
+    assert_eq!(comps.len(), 2);
+    for sub in &comps {
+        assert_eq!(sub.num_constraints(), 3);
+        assert_eq!(Solver::new(sub.clone()).count_solutions(None), 2);
+    }
+
+    assert_eq!(dominoes(2, 3).components().len(), 1);
+}
+
+#[test]
+fn product_of_components() {
+    let solver = Solver::new(two_sides());
+    assert_eq!(solver.count_solutions(None), 4);
+    assert_eq!(solver.count_solutions(Some(3)), 3);
+
+    let sols = sorted(solver.clone().into_iter());
+    let mut split = solver;
+    split.set_decompose(true);
+    assert_eq!(sorted(split.into_iter()), sols);
+    assert_eq!(sols.len(), 4);
+    for s in &sols {
+        assert_eq!(s.iter().filter(|a| a.0 == 0).count(), 2);
+        assert_eq!(s.iter().filter(|a| a.0 == 1).count(), 2);
+    }
+    let mut deduped = sols.clone();
+    deduped.dedup();
+    assert_eq!(deduped, sols);
+}
+
+#[test]
+fn unsolvable_component() {
+    let mut p = two_sides();
+    p.add_constraint(&(2, 0));
+    assert_eq!(p.components().len(), 3);
+
+    let mut solver = Solver::new(p);
+    assert_eq!(solver.count_solutions(None), 0);
+    assert_eq!(solver.clone().into_iter().count(), 0);
+    solver.set_decompose(true);
+    assert_eq!(solver.into_iter().count(), 0);
+}
+
+#[test]
+fn secondary_constraint_joins_components() {
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&"s");
+    p.add_action(0, &["a", "s"]).unwrap();
+    p.add_action(1, &["a"]).unwrap();
+    p.add_action(2, &["b", "s"]).unwrap();
+    p.add_action(3, &["b"]).unwrap();
+    assert_eq!(p.components().len(), 1);
+    assert_eq!(Solver::new(p).count_solutions(None), 3);
+}
+
+#[test]
+fn required_actions_split_problem() {
+    // Covering the middle column of a 4 x 3 board leaves two 4 x 1
+    // strips.
+    let mut solver = Solver::new(dominoes(4, 3));
+    solver.require_action((0, 1, false)).unwrap();
+    solver.require_action((2, 1, false)).unwrap();
+    assert_eq!(solver.problem().components().len(), 2);
+
+    let expected = solver.clone().into_iter().count();
+    assert_eq!(expected, 1);
+    assert_eq!(solver.count_solutions(None), expected);
+
+    solver.set_decompose(true);
+    let sol = solver.into_iter().next().unwrap();
+    assert_eq!(&sol[..2], &[(0, 1, false), (2, 1, false)]);
+}
+
+#[test]
+fn decompose_during_search() {
+    let mut solver = Solver::new(dominoes(4, 4));
+    assert_eq!(solver.count_solutions(None), 36);
+    solver.set_decompose(true);
+    assert_eq!(solver.count_solutions(None), 36);
+    assert_eq!(solver.count_solutions(Some(10)), 10);
+    assert_eq!(solver.par_count_solutions(2), 36);
+
+    let mut solver = Solver::new(dominoes(3, 6));
+    let expected = solver.count_solutions(None);
+    solver.set_decompose(true);
+    assert_eq!(solver.count_solutions(None), expected);
+    assert_eq!(expected, sorted(solver.into_iter()).len());
+}
+
+#[test]
+fn split_bounded_and_colored() {
+    // Two teams of two people, each person working one or two of
+    // their team's three shifts. Shifts of the same team share a
+    // colored constraint with the person's team.
+    let mut p = Problem::new();
+    for team in 0..2 {
+        p.add_secondary_constraint(&(team, 9, 9));
+        for person in 0..2 {
+            p.add_bounded_constraint(&(team, person, 8), 1, 2);
+        }
+    }
+    for team in 0..2 {
+        for person in 0..2 {
+            for shift in 0..3 {
+                p.add_colored_action((team, person, shift), &[(team, person, 8), (team, 3, shift)],
+                                     &[((team, 9, 9), team)]).unwrap();
+            }
+        }
+    }
+
+    let mut solver = Solver::new(p);
+    assert_eq!(solver.problem().components().len(), 2);
+    assert_eq!(solver.count_solutions(None), 36);
+
+    solver.require_action((0, 0, 0)).unwrap();
+    assert_eq!(solver.problem().components().len(), 2);
+    let sols = sorted(solver.clone().into_iter());
+    assert_eq!(sols.len(), solver.count_solutions(None));
+    assert_eq!(sols.len(), 3 * 6);
+    solver.set_decompose(true);
+    assert_eq!(sorted(solver.into_iter()), sols);
+}
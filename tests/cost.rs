@@ -0,0 +1,100 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver};
+
+fn test_prob() -> Problem<&'static str, usize> {
+    let mut p = Problem::new();
+    p.add_action("01", &[0, 1]).unwrap();
+    p.add_action("03", &[0, 3]).unwrap();
+    p.add_action("12", &[1, 2]).unwrap();
+    p.add_action("23", &[2, 3]).unwrap();
+    p.add_action("0", &[0]).unwrap();
+    p.add_action("1", &[1]).unwrap();
+    p.add_action("2", &[2]).unwrap();
+    p.add_action("3", &[3]).unwrap();
+
+    p.set_action_cost("01", 5);
+    p.set_action_cost("03", 2);
+    p.set_action_cost("12", 2);
+    p.set_action_cost("23", 6);
+    p.set_action_cost("0", 3);
+    p.set_action_cost("1", 1);
+    p.set_action_cost("2", 2);
+    p.set_action_cost("3", 4);
+
+    p
+}
+
+fn cost_of(p: &Problem<&'static str, usize>, sol: &[&'static str]) -> u64 {
+    sol.iter().map(|a| p.action_cost(*a).unwrap()).sum()
+}
+
+#[test]
+fn min_cost() {
+    let (sol, cost) = Solver::new(test_prob()).min_cost_solution().unwrap();
+
+    let p = test_prob();
+    assert_eq!(cost, cost_of(&p, &sol));
+
+    let best = Solver::new(test_prob()).into_iter().map(|s| cost_of(&p, &s)).min().unwrap();
+    assert_eq!(cost, best);
+    assert_eq!(cost, 4);
+}
+
+#[test]
+fn within_cost() {
+    let p = test_prob();
+    for bound in 0..12 {
+        let mut within: Vec<u64> = Solver::new(test_prob()).solutions_within_cost(bound)
+            .into_iter().map(|(_, c)| c).collect();
+        within.sort();
+
+        let mut expected: Vec<u64> = Solver::new(test_prob()).into_iter()
+            .map(|s| cost_of(&p, &s)).filter(|c| *c <= bound).collect();
+        expected.sort();
+
+        assert_eq!(within, expected);
+    }
+}
+
+#[test]
+fn min_cost_with_required() {
+    let mut solver = Solver::new(test_prob());
+    assert!(solver.require_action("01").is_ok());
+
+    let (mut sol, cost) = solver.min_cost_solution().unwrap();
+    sol.sort();
+    assert_eq!(sol, vec!["01", "23"]);
+    assert_eq!(cost, 11);
+}
+
+#[test]
+fn min_cost_no_solution() {
+    let mut p = Problem::new();
+    p.add_action(0, &[0, 1]).unwrap();
+    p.add_action(1, &[1, 2]).unwrap();
+    p.set_action_cost(0, 1);
+
+    assert!(Solver::new(p).min_cost_solution().is_none());
+}
+
+#[test]
+fn huge_costs() {
+    let mut p = Problem::new();
+    p.add_action("a", &[0]).unwrap();
+    p.add_action("b", &[1]).unwrap();
+    p.add_action("ab", &[0, 1]).unwrap();
+    p.set_action_cost("a", u64::MAX);
+    p.set_action_cost("b", u64::MAX);
+    p.set_action_cost("ab", u64::MAX - 1);
+
+    // "a" and "b" together cost more than "ab", not less.
+    assert_eq!(Solver::new(p.clone()).min_cost_solution(), Some((vec!["ab"], u64::MAX - 1)));
+    assert_eq!(Solver::new(p.clone()).solutions_within_cost(u64::MAX).len(), 2);
+
+    let mut solver = Solver::new(p);
+    solver.require_action("a").unwrap();
+    solver.require_action("b").unwrap();
+    assert_eq!(solver.min_cost_solution().map(|s| s.1), Some(u64::MAX));
+}
@@ -6,10 +6,37 @@ use exact_cover::{Solver};
 #[test]
 fn solve_problem() {
     let mut p = Problem::new();
-    p.add_action(0, &[0, 1, 2]);
-    p.add_action(1, &[3, 4]);
-    p.add_action(2, &[2, 4]);
+    p.add_action(0, &[0, 1, 2]).unwrap();
+    p.add_action(1, &[3, 4]).unwrap();
+    p.add_action(2, &[2, 4]).unwrap();
 
     let solver = Solver::new(p);
     assert!(solver.first_solution().is_some());
 }
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn problem_is_send() {
+    assert_send::<Problem<usize, &'static str>>();
+    assert_send::<Solver<usize, &'static str>>();
+    assert_sync::<Problem<usize, &'static str>>();
+    assert_sync::<Solver<usize, &'static str>>();
+}
+
+#[test]
+fn add_constraint_after_actions() {
+    let mut p = Problem::new();
+    p.add_action(0, &[0, 1]).unwrap();
+    p.add_constraint(&2);
+    p.add_action(1, &[2]).unwrap();
+    p.add_action(2, &[1, 2]).unwrap();
+
+    let solver = Solver::new(p.clone());
+    assert_eq!(solver.first_solution().map(|s| s.len()), Some(2));
+
+    // Solving doesn't change the problem.
+    assert_eq!(solver.first_solution().map(|s| s.len()), Some(2));
+    assert_eq!(Solver::new(p).into_iter().count(), 1);
+}
@@ -0,0 +1,140 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem, Solver, Error};
+use exact_cover::formats::dlx;
+
+#[test]
+fn knuth_example() {
+    // The example from TAOCP 7.2.2.1.
+    let input = "| Knuth's example\n\
+                 a b c d e f g\n\
+                 c e\n\
+                 a d g\n\
+                 b c f\n\
+                 a d f\n\
+                 b g\n\
+                 d e g\n";
+    let p = dlx::parse(input).unwrap();
+    assert_eq!(p.num_constraints(), 7);
+    assert_eq!(p.num_actions(), 6);
+
+    let mut sol = Solver::new(p).first_solution().unwrap();
+    sol.sort();
+    assert_eq!(sol, vec![0, 3, 4]);
+}
+
+#[test]
+fn secondary_items_and_colors() {
+    let input = "a b c | x\n\
+                 \n\
+                 a b\n\
+                 c x:red\n\
+                 a c x:blue\n\
+                 b\n\
+                 c x:blue\n";
+    let p = dlx::parse(input).unwrap();
+    assert!(p.is_secondary(&"x".to_string()));
+
+    let mut sols: Vec<Vec<usize>> = Solver::new(p).into_iter()
+        .map(|mut s| { s.sort(); s })
+        .collect();
+    sols.sort();
+    assert_eq!(sols, vec![vec![0, 1], vec![0, 4], vec![2, 3]]);
+}
+
+#[test]
+fn multiplicities() {
+    let p = dlx::parse("2|a 0:2|b c\na b\na c\na\nb c\n").unwrap();
+    assert_eq!(p.constraint_bounds(&"a".to_string()), Some((2, 2)));
+    assert_eq!(p.constraint_bounds(&"b".to_string()), Some((0, 2)));
+    // {0, 1}, {1, 2} and {0, 2, 3}
+    assert_eq!(Solver::new(p).count_solutions(None), 3);
+}
+
+#[test]
+fn write_problem() {
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&"m".to_string(), 1, 3);
+    p.add_bounded_constraint(&"n".to_string(), 2, 2);
+    p.add_secondary_constraint(&"s".to_string());
+    p.add_action('x', &["a".to_string(), "m".to_string()]).unwrap();
+    p.add_colored_action('y', &["n".to_string()], &[("s".to_string(), 4)]).unwrap();
+    p.add_action('z', &["n".to_string(), "m".to_string()]).unwrap();
+
+    let text = dlx::to_string(&p).unwrap();
+    assert_eq!(text, "1:3|m 2|n a | s\na m\nn s:4\nn m\n");
+
+    let q = dlx::parse(&text).unwrap();
+    assert_eq!(dlx::to_string(&q).unwrap(), text.replace("s:4", "s:0"));
+}
+
+#[test]
+fn round_trip() {
+    let n = 6;
+    let mut p = Problem::new();
+    for i in 0..(2*n - 1) {
+        p.add_secondary_constraint(&format!("a{}", i));
+        p.add_secondary_constraint(&format!("b{}", i));
+    }
+    for r in 0..n {
+        for c in 0..n {
+            p.add_action((r, c), &[format!("r{}", r), format!("c{}", c),
+                                   format!("a{}", r + c), format!("b{}", n - 1 + r - c)]).unwrap();
+        }
+    }
+
+    let q = dlx::parse(&dlx::to_string(&p).unwrap()).unwrap();
+    assert_eq!(q.num_actions(), n * n);
+    assert_eq!(q.num_secondary_constraints(), 2 * (2*n - 1));
+    assert_eq!(Solver::new(q).count_solutions(None), 4);
+}
+
+#[test]
+fn no_primary_items() {
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&"x".to_string());
+    p.add_action(0, &["x".to_string()]).unwrap();
+    let e = dlx::to_string(&p).unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+
+    // Bounded items are written among the primary ones, so they
+    // round-trip even if they are optional.
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&"y".to_string(), 0, 2);
+    p.add_secondary_constraint(&"x".to_string());
+    p.add_action(0, &["x".to_string(), "y".to_string()]).unwrap();
+    p.add_action(1, &["y".to_string()]).unwrap();
+    let text = dlx::to_string(&p).unwrap();
+    assert_eq!(text, "0:2|y | x\nx y\ny\n");
+    assert_eq!(dlx::to_string(&dlx::parse(&text).unwrap()).unwrap(), text);
+}
+
+#[test]
+fn invalid_names() {
+    let mut p = Problem::new();
+    p.add_action(0, &["a b".to_string()]).unwrap();
+    assert!(dlx::to_string(&p).is_err());
+}
+
+fn error_line(input: &str) -> usize {
+    match dlx::parse(input) {
+        Err(Error::Syntax { line, .. }) => line,
+        r => panic!("expected a syntax error, got {:?}", r.map(|p| p.num_actions()))
+    }
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(error_line(""), 0);
+    assert_eq!(error_line("a b\na c\n"), 2);
+    assert_eq!(error_line("a b\n| comment\na a\n"), 3);
+    assert_eq!(error_line("a | x\na:red\n"), 2);
+    assert_eq!(error_line("a | x\na x:\n"), 2);
+    assert_eq!(error_line("a a\n"), 1);
+    assert_eq!(error_line("3:1|a\n"), 1);
+    assert_eq!(error_line("x|a\n"), 1);
+    assert_eq!(error_line("a | x | y\n"), 1);
+
+    let e = dlx::parse("a\nb\n").err().unwrap();
+    assert_eq!(e.to_string(), "line 2: unknown item \"b\"");
+}
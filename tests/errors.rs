@@ -0,0 +1,84 @@
+extern crate exact_cover;
+
+use std::error;
+
+use exact_cover::{Problem, Solver, Error};
+use exact_cover::instances::sudoku::{sudoku_solver, SudokuAction, SudokuConstraint, Location};
+
+#[test]
+fn add_action_errors() {
+    let mut p = Problem::new();
+    assert_eq!(p.add_action(0, &["a", "b"]), Ok(()));
+    assert_eq!(p.add_action(0, &["c"]), Err(Error::DuplicateAction(0)));
+    assert_eq!(p.add_action(1, &[]), Err(Error::EmptyAction(1)));
+    assert_eq!(p.add_colored_action(2, &["c"], &[("a", 1)]),
+               Err(Error::ColoredPrimary { action: 2, constraint: "a" }));
+
+    // Rejected actions leave no trace.
+    assert_eq!(p.num_actions(), 1);
+    assert_eq!(p.num_constraints(), 2);
+}
+
+#[test]
+fn require_errors() {
+    let mut p = Problem::new();
+    p.add_action(0, &["a", "b"]).unwrap();
+    p.add_action(1, &["b", "c"]).unwrap();
+    p.add_action(2, &["c"]).unwrap();
+    assert!(!p.can_require_row(5));
+
+    let mut solver = Solver::new(p);
+    assert_eq!(solver.require_action(5), Err(Error::UnknownAction(5)));
+    assert_eq!(solver.require_action(0), Ok(()));
+    assert_eq!(solver.require_action(1), Err(Error::Conflict { action: 1, constraint: "b" }));
+    assert_eq!(solver.first_solution(), Some(vec![2, 0]));
+}
+
+#[test]
+fn require_twice() {
+    // Every constraint could take both copies of the action.
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&"a", 1, 2);
+    p.add_bounded_constraint(&"b", 0, 2);
+    p.add_action(0, &["a", "b"]).unwrap();
+    p.add_action(1, &["a"]).unwrap();
+
+    let mut solver = Solver::new(p);
+    assert_eq!(solver.require_action(0), Ok(()));
+    assert!(!solver.problem().can_require_row(0));
+    assert_eq!(solver.require_action(0), Err(Error::AlreadyRequired(0)));
+    assert_eq!(solver.required_actions(), &[0]);
+
+    let mut sols: Vec<Vec<u8>> = solver.into_iter().map(|mut s| { s.sort(); s }).collect();
+    sols.sort();
+    assert_eq!(sols, vec![vec![0], vec![0, 1]]);
+}
+
+#[test]
+fn sudoku_errors() {
+    assert_eq!(sudoku_solver(&[0; 80]).err(), Some(Error::InvalidSudokuSize(80)));
+    assert_eq!(sudoku_solver(&[]).err(), Some(Error::InvalidSudokuSize(0)));
+    // 2x2 grids have no boxes.
+    assert_eq!(sudoku_solver(&[0; 4]).err(), Some(Error::InvalidSudokuSize(4)));
+
+    let mut cells = [0; 16];
+    cells[6] = 5;
+    assert_eq!(sudoku_solver(&cells).err(), Some(Error::InvalidSudokuCell { row: 1, col: 2, value: 5 }));
+
+    cells[6] = 3;
+    cells[7] = 3;
+    assert_eq!(sudoku_solver(&cells).err(),
+               Some(Error::Conflict { action: SudokuAction::new(3, 1, 3),
+                                      constraint: SudokuConstraint::Uniqueness(3, Location::Row(1)) }));
+}
+
+#[test]
+fn error_messages() {
+    let e: Box<dyn error::Error> = Box::new(Error::Conflict::<u8, &str> { action: 1, constraint: "b" });
+    assert_eq!(e.to_string(), "action 1 conflicts with constraint \"b\", which is already full or has another color");
+    assert_eq!(Error::ColoredPrimary::<u8, &str> { action: 1, constraint: "b" }.to_string(),
+               "action 1 colors constraint \"b\", which is not secondary");
+    assert_eq!(Error::InvalidSudokuCell::<u8, u8> { row: 1, col: 2, value: 5 }.to_string(),
+               "invalid sudoku entry 5 at (1, 2)");
+    assert_eq!(Error::AlreadyRequired::<u8, u8>(3).to_string(), "action 3 was already required");
+}
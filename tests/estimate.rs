@@ -0,0 +1,74 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Problem, Solver};
+use common::{queens_problem};
+
+/// Assigning n people to n jobs: every path down the search tree
+/// looks the same.
+fn permutations(n: usize) -> Problem<(usize, usize), (bool, usize)> {
+    let mut p = Problem::new();
+    for i in 0..n {
+        for j in 0..n {
+            p.add_action((i, j), &[(false, i), (true, j)]).unwrap();
+        }
+    }
+    p
+}
+
+#[test]
+fn uniform_tree_is_exact() {
+    let solver = Solver::new(permutations(5));
+    let estimate = solver.estimate(10, 1);
+    assert_eq!(estimate.probes, 10);
+    assert_eq!(estimate.solutions, 120.0);
+    assert_eq!(estimate.solutions_variance, 0.0);
+    // 5 + 5*4 + 5*4*3 + 5*4*3*2 + 5*4*3*2*1
+    assert_eq!(estimate.nodes, 325.0);
+    assert_eq!(estimate.nodes_variance, 0.0);
+
+    let (count, stats) = solver.count_solutions_with_stats(None);
+    assert_eq!(count, 120);
+    assert_eq!(stats.nodes, 325);
+}
+
+#[test]
+fn queens_estimate() {
+    let solver = Solver::new(queens_problem(8));
+    let (count, stats) = solver.count_solutions_with_stats(None);
+    let estimate = solver.estimate(5000, 7);
+
+    let nodes = stats.nodes as f64;
+    assert!((estimate.nodes - nodes).abs() < 4.0 * estimate.nodes_std_error(),
+            "{:?} vs {}", estimate, nodes);
+    assert!((estimate.solutions - count as f64).abs() < 4.0 * estimate.solutions_std_error(),
+            "{:?} vs {}", estimate, count);
+    assert!(estimate.solutions_variance > 0.0);
+}
+
+#[test]
+fn no_solutions() {
+    let estimate = Solver::new(queens_problem(3)).estimate(100, 3);
+    assert_eq!(estimate.solutions, 0.0);
+    assert!(estimate.nodes > 0.0);
+}
+
+#[test]
+fn same_seed_same_estimate() {
+    let solver = Solver::new(queens_problem(10));
+    assert_eq!(solver.estimate(50, 42), solver.estimate(50, 42));
+}
+
+#[test]
+fn estimate_leaves_solver_intact() {
+    let solver = Solver::new(queens_problem(6));
+    solver.estimate(100, 5);
+    assert_eq!(solver.count_solutions(None), 4);
+}
+
+#[test]
+#[should_panic(expected = "at least one probe")]
+fn zero_probes() {
+    Solver::new(queens_problem(4)).estimate(0, 1);
+}
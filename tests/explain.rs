@@ -0,0 +1,88 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem, Solver, Infeasibility, SearchLimits};
+use exact_cover::instances::sudoku::{sudoku_solver, SudokuAction, SudokuConstraint, Location};
+
+#[test]
+fn satisfiable() {
+    let mut p = Problem::new();
+    p.add_action("x", &[1, 2]).unwrap();
+    p.add_action("y", &[3]).unwrap();
+    let mut solver = Solver::new(p);
+    assert_eq!(solver.explain_infeasibility(), None);
+    solver.require_action("y").unwrap();
+    assert_eq!(solver.explain_infeasibility(), None);
+}
+
+#[test]
+fn required_actions() {
+    // Requiring a and b leaves no way to satisfy 3; e is irrelevant.
+    let mut p = Problem::new();
+    p.add_action("a", &[1]).unwrap();
+    p.add_action("b", &[2]).unwrap();
+    p.add_action("c", &[1, 3]).unwrap();
+    p.add_action("d", &[2, 3]).unwrap();
+    p.add_action("e", &[4]).unwrap();
+    p.add_action("f", &[4]).unwrap();
+    let mut solver = Solver::new(p);
+    for a in ["a", "e", "b"] {
+        solver.require_action(a).unwrap();
+    }
+
+    let explanation = solver.explain_infeasibility().unwrap();
+    assert_eq!(explanation, Infeasibility { actions: vec!["a", "b"], constraints: vec![1, 2, 3], minimal: true });
+    assert_eq!(explanation.to_string(), "required actions [\"a\", \"b\"] leave constraints [1, 2, 3] unsatisfiable");
+}
+
+#[test]
+fn unsatisfiable_problem() {
+    let mut p = Problem::new();
+    p.add_constraint(&"empty");
+    p.add_bounded_constraint(&"pair", 2, 2);
+    p.add_action(0, &["pair"]).unwrap();
+    p.add_action(1, &["pair"]).unwrap();
+    p.add_action(2, &["other"]).unwrap();
+    let explanation = Solver::new(p).explain_infeasibility().unwrap();
+    assert!(explanation.actions.is_empty());
+    assert_eq!(explanation.constraints, vec!["empty"]);
+}
+
+#[test]
+fn sudoku_clues() {
+    // The top-left box has no room for a 3: its top row is filled,
+    // and its bottom row already has a 3. The bottom clues don't
+    // matter.
+    let cells = [1, 2, 0, 0,
+                 0, 0, 3, 0,
+                 0, 0, 4, 0,
+                 0, 0, 0, 3];
+    let solver = sudoku_solver(&cells).unwrap();
+    assert_eq!(solver.first_solution(), None);
+
+    let explanation = solver.explain_infeasibility().unwrap();
+    assert!(explanation.minimal);
+    assert_eq!(explanation.actions, vec![SudokuAction::new(1, 0, 0), SudokuAction::new(2, 0, 1),
+                                         SudokuAction::new(3, 1, 2)]);
+    assert_eq!(explanation.constraints, vec![SudokuConstraint::Existence(0, 0), SudokuConstraint::Existence(0, 1),
+                                             SudokuConstraint::Uniqueness(3, Location::Box(0, 0)),
+                                             SudokuConstraint::Uniqueness(3, Location::Row(1))]);
+}
+
+#[test]
+fn limits() {
+    // Three queens on a 3x3 board, with no room to search.
+    let mut p = Problem::new();
+    for r in 0..3 {
+        for c in 0..3 {
+            p.add_action((r, c), &[(0, r), (1, c), (2, r + c), (3, 2 + r - c)]).unwrap();
+        }
+    }
+    for i in 0..5 {
+        p.add_secondary_constraint(&(2, i));
+        p.add_secondary_constraint(&(3, i));
+    }
+    let mut solver = Solver::new(p);
+    assert!(solver.explain_infeasibility().unwrap().minimal);
+    solver.set_limits(SearchLimits::new().max_nodes(0));
+    assert_eq!(solver.explain_infeasibility(), None);
+}
@@ -0,0 +1,251 @@
+extern crate exact_cover;
+
+use std::collections::{HashMap, HashSet};
+
+use exact_cover::{Problem, Solver};
+use exact_cover::formats::ilp::Ilp;
+
+/// A row of an LP file: its terms, sense and right-hand side.
+type Row = (Vec<(i64, String)>, String, i64);
+
+/// Read the rows and variables back from an LP file.
+fn parse_lp(lp: &str) -> (Vec<Row>, Vec<String>) {
+    let mut rows = Vec::new();
+    let mut vars = Vec::new();
+    let mut section = "";
+    for line in lp.lines() {
+        if !line.starts_with(' ') {
+            section = line;
+            continue;
+        }
+        match section {
+            "Subject To" => {
+                let tokens: Vec<&str> = line.split_whitespace().skip(1).collect();
+                let n = tokens.len();
+                let mut terms = Vec::new();
+                let mut sign = 1;
+                let mut coef = 1;
+                for t in &tokens[..n - 2] {
+                    match *t {
+                        "+" => sign = 1,
+                        "-" => sign = -1,
+                        t if t.starts_with('-') => terms.push((-coef, t[1..].to_string())),
+                        t => match t.parse::<i64>() {
+                            Ok(c) => coef = c,
+                            Err(_) => {
+                                terms.push((sign * coef, t.to_string()));
+                                sign = 1;
+                                coef = 1;
+                            }
+                        }
+                    }
+                }
+                rows.push((terms, tokens[n - 2].to_string(), tokens[n - 1].parse().unwrap()));
+            }
+            "Bounds" => {
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                rows.push((vec![(1, tokens[0].to_string())], "=".to_string(), tokens[2].parse().unwrap()));
+            }
+            "Binaries" => vars.push(line.trim().to_string()),
+            _ => {}
+        }
+    }
+    (rows, vars)
+}
+
+/// Return the feasible points of the program, as sets of actions.
+fn feasible(ilp: &Ilp<u32>, num_actions: usize) -> HashSet<Vec<u32>> {
+    let (rows, vars) = parse_lp(&ilp.to_lp().unwrap());
+    assert_eq!(vars.len(), ilp.num_vars());
+    let mut found = HashSet::new();
+    for bits in 0..(1u64 << vars.len()) {
+        let value: HashMap<&str, i64> = vars.iter().enumerate()
+            .map(|(i, v)| (v.as_str(), (bits >> i & 1) as i64))
+            .collect();
+        let ok = rows.iter().all(|(terms, sense, rhs)| {
+            let lhs: i64 = terms.iter().map(|(c, v)| c * value[v.as_str()]).sum();
+            match sense.as_str() {
+                "=" => lhs == *rhs,
+                "<=" => lhs <= *rhs,
+                ">=" => lhs >= *rhs,
+                s => panic!("bad sense {}", s)
+            }
+        });
+        if ok {
+            let values: Vec<f64> = (0..num_actions).map(|i| (bits >> i & 1) as f64).collect();
+            let mut sol = ilp.solution(&values);
+            sol.sort();
+            found.insert(sol);
+        }
+    }
+    found
+}
+
+fn check<C: Clone + Eq + std::hash::Hash>(solver: Solver<u32, C>) {
+    let expected: HashSet<Vec<u32>> = solver.clone().into_iter()
+        .map(|mut s| { s.sort(); s })
+        .collect();
+    let ilp = Ilp::from_solver(&solver);
+    assert_eq!(feasible(&ilp, solver.problem().num_actions()), expected);
+}
+
+#[test]
+fn knuth_example() {
+    let mut p = Problem::new();
+    for (i, items) in ["ce", "adg", "bcf", "adf", "bg", "deg"].iter().enumerate() {
+        p.add_action(i as u32, &items.chars().collect::<Vec<_>>()).unwrap();
+    }
+    check(Solver::new(p));
+}
+
+#[test]
+fn bounds_and_colors() {
+    // Assign 3 people to 2 shifts of 1 to 2 people, where people 0
+    // and 1 wear a color on shift 0, and person 2 none.
+    let mut p = Problem::new();
+    for s in 0..2 {
+        p.add_bounded_constraint(&(0, s), 1, 2);
+    }
+    p.add_secondary_constraint(&(1, 0));
+    for i in 0..3 {
+        for s in 0..2 {
+            let a = (2 * i + s) as u32;
+            if s == 0 && i < 2 {
+                p.add_colored_action(a, &[(2, i), (0, s)], &[((1, 0), i)]).unwrap();
+            } else if s == 0 {
+                p.add_action(a, &[(2, i), (0, s), (1, 0)]).unwrap();
+            } else {
+                p.add_action(a, &[(2, i), (0, s)]).unwrap();
+            }
+        }
+    }
+    check(Solver::new(p));
+}
+
+#[test]
+fn required_actions() {
+    let mut p = Problem::new();
+    for i in 0..4 {
+        p.add_action(i, &[i % 2, 2 + i / 2]).unwrap();
+    }
+    p.add_action(4, &[0, 1, 2, 3]).unwrap();
+    let mut solver = Solver::new(p);
+    check(solver.clone());
+    solver.require_action(4).unwrap();
+    check(solver);
+}
+
+#[test]
+fn secondary_only_actions() {
+    // Actions 2 and 3 satisfy no primary constraint, so the solver
+    // never picks them unless they are required.
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&'s');
+    p.add_secondary_constraint(&'t');
+    p.add_action(0, &['a', 's']).unwrap();
+    p.add_action(1, &['a']).unwrap();
+    p.add_action(2, &['t']).unwrap();
+    p.add_colored_action(3, &[], &[('s', 1)]).unwrap();
+    let mut solver = Solver::new(p);
+    check(solver.clone());
+    assert_eq!(solver.clone().into_iter().count(), 2);
+
+    solver.require_action(2).unwrap();
+    check(solver);
+}
+
+#[test]
+fn formats() {
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&'a', 1, 2);
+    p.add_secondary_constraint(&'s');
+    p.add_action("x", &['a']).unwrap();
+    p.add_colored_action("y", &['a'], &[('s', 1)]).unwrap();
+    p.add_action("z", &['b', 's']).unwrap();
+    p.set_action_cost("y", 3);
+    let mut solver = Solver::new(p);
+    solver.require_action("z").unwrap();
+    let ilp = Ilp::from_solver(&solver);
+    assert_eq!(ilp.variable("y"), Some(1));
+
+    assert_eq!(ilp.to_lp().unwrap(), "\
+Minimize
+ obj: 3 x1
+Subject To
+ c0_lo: x0 + x1 >= 1
+ c1_x1: x1 - y1_1 <= 0
+ c1_x2: y1_1 + x2 <= 1
+ c2: x2 = 1
+Bounds
+ x2 = 1
+Binaries
+ x0
+ x1
+ x2
+ y1_1
+End
+");
+
+    assert_eq!(ilp.to_mps().unwrap(), "\
+NAME exact_cover
+ROWS
+ N obj
+ G c0_lo
+ L c1_x1
+ L c1_x2
+ E c2
+COLUMNS
+ MARKER 'MARKER' 'INTORG'
+ x0 c0_lo 1
+ x1 obj 3
+ x1 c0_lo 1
+ x1 c1_x1 1
+ x2 c1_x2 1
+ x2 c2 1
+ y1_1 c1_x1 -1
+ y1_1 c1_x2 1
+ MARKER 'MARKER' 'INTEND'
+RHS
+ rhs c0_lo 1
+ rhs c1_x2 1
+ rhs c2 1
+BOUNDS
+ BV bnd x0
+ BV bnd x1
+ FX bnd x2 1
+ BV bnd y1_1
+ENDATA
+");
+}
+
+#[test]
+fn solutions() {
+    let mut p: Problem<&str, u8> = Problem::new();
+    p.add_action("x", &[0]).unwrap();
+    p.add_action("y", &[0]).unwrap();
+    p.add_action("z", &[1]).unwrap();
+    let ilp = Ilp::from_problem(&p);
+    assert_eq!(ilp.solution(&[0.0, 0.9999, 1.0]), vec!["y", "z"]);
+
+    let gurobi = "# Objective value = 0\nx0 0\nx1 1\nx2 1\n";
+    assert_eq!(ilp.parse_solution(gurobi), Ok(vec!["y", "z"]));
+    let cbc = "Optimal - objective value 0\n      0 x0  1  0\n      2 x2  1  0\n";
+    assert_eq!(ilp.parse_solution(cbc), Ok(vec!["x", "z"]));
+    assert!(ilp.parse_solution("x1\n").is_err());
+}
+
+#[test]
+fn huge_costs() {
+    let mut p: Problem<&str, u8> = Problem::new();
+    p.add_action("x", &[0]).unwrap();
+    p.add_action("y", &[0]).unwrap();
+    p.set_action_cost("x", i64::MAX as u64);
+    let ilp = Ilp::from_problem(&p);
+    assert!(ilp.to_lp().unwrap().contains(&format!(" obj: {} x0\n", i64::MAX)));
+
+    // Costs are written as they are, or not at all.
+    p.set_action_cost("y", i64::MAX as u64 + 1);
+    let ilp = Ilp::from_problem(&p);
+    assert!(ilp.to_lp().is_err());
+    assert!(ilp.to_mps().is_err());
+}
@@ -0,0 +1,115 @@
+extern crate exact_cover;
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use exact_cover::{Problem};
+use exact_cover::{Solver, SearchLimits, SearchResult, StopReason};
+use common::{queens_problem};
+
+/// A problem with no solution but a huge search tree: placing 13
+/// pigeons in 12 holes.
+fn hopeless() -> Solver<(usize, usize), (bool, usize)> {
+    let mut p = Problem::new();
+    for hole in 0..12 {
+        p.add_secondary_constraint(&(false, hole));
+    }
+    for pigeon in 0..13 {
+        for hole in 0..12 {
+            p.add_action((pigeon, hole), &[(true, pigeon), (false, hole)]).unwrap();
+        }
+    }
+    Solver::new(p)
+}
+
+#[test]
+fn no_limits() {
+    let solver = Solver::new(queens_problem(6));
+    assert!(solver.try_first_solution().found().is_some());
+    assert_eq!(Solver::new(queens_problem(3)).try_first_solution(), SearchResult::NoSolution);
+}
+
+#[test]
+fn node_limit() {
+    let mut solver = Solver::new(queens_problem(8));
+    solver.set_limits(SearchLimits::new().max_nodes(3));
+    assert_eq!(solver.try_first_solution(), SearchResult::GaveUp(StopReason::NodeLimit));
+    assert!(solver.first_solution().is_none());
+
+    solver.set_limits(SearchLimits::new().max_nodes(1_000_000));
+    assert!(solver.try_first_solution().found().is_some());
+
+    let mut solver = Solver::new(queens_problem(3));
+    solver.set_limits(SearchLimits::new().max_nodes(1_000_000));
+    assert_eq!(solver.try_first_solution(), SearchResult::NoSolution);
+}
+
+#[test]
+fn iterator_resumes() {
+    let expected: Vec<_> = Solver::new(queens_problem(8)).into_iter().collect();
+
+    let mut solver = Solver::new(queens_problem(8));
+    solver.set_limits(SearchLimits::new().max_nodes(50));
+    let mut iter = solver.into_iter();
+
+    let mut sols = Vec::new();
+    let mut gave_up = 0;
+    loop {
+        match iter.try_next() {
+            SearchResult::Found(s) => sols.push(s),
+            SearchResult::NoSolution => break,
+            SearchResult::GaveUp(reason) => {
+                assert_eq!(reason, StopReason::NodeLimit);
+                assert_eq!(iter.stopped(), Some(StopReason::NodeLimit));
+                gave_up += 1;
+                iter.set_limits(SearchLimits::new().max_nodes(50));
+            }
+        }
+    }
+    assert!(gave_up > 10);
+    assert_eq!(iter.stopped(), None);
+    assert_eq!(sols, expected);
+}
+
+#[test]
+fn iterator_stops_with_none() {
+    let mut solver = Solver::new(queens_problem(8));
+    solver.set_limits(SearchLimits::new().max_nodes(200));
+    let mut iter = solver.into_iter();
+    let n = iter.by_ref().count();
+    assert!(n < 92);
+    assert_eq!(iter.stopped(), Some(StopReason::NodeLimit));
+}
+
+#[test]
+fn deadline() {
+    let mut solver = hopeless();
+    solver.set_limits(SearchLimits::new().timeout(Duration::from_millis(20)));
+    let start = Instant::now();
+    assert_eq!(solver.try_first_solution(), SearchResult::GaveUp(StopReason::Deadline));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    solver.set_limits(SearchLimits::new().deadline(Instant::now()));
+    assert_eq!(solver.into_iter().try_next(), SearchResult::GaveUp(StopReason::Deadline));
+}
+
+#[test]
+fn cancel() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut solver = hopeless();
+    solver.set_limits(SearchLimits::new().cancel_flag(flag.clone()));
+
+    let canceller = {
+        let flag = flag.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            flag.store(true, Ordering::Relaxed);
+        })
+    };
+    assert_eq!(solver.try_first_solution(), SearchResult::GaveUp(StopReason::Cancelled));
+    canceller.join().unwrap();
+}
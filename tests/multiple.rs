@@ -6,10 +6,10 @@ use exact_cover::{Solver};
 #[test]
 fn first_of_many() {
     let mut p = Problem::new();
-    p.add_action(0, &[0, 1]);
-    p.add_action(1, &[2, 3]);
-    p.add_action(2, &[0, 3]);
-    p.add_action(3, &[1, 2]);
+    p.add_action(0, &[0, 1]).unwrap();
+    p.add_action(1, &[2, 3]).unwrap();
+    p.add_action(2, &[0, 3]).unwrap();
+    p.add_action(3, &[1, 2]).unwrap();
 
     let solver = Solver::new(p);
     let sol = solver.first_solution().unwrap();
@@ -19,14 +19,14 @@ fn first_of_many() {
 
 fn test_prob() -> Problem<&'static str, usize> {
     let mut p = Problem::new();
-    p.add_action("01", &[0, 1]);
-    p.add_action("03", &[0, 3]);
-    p.add_action("12", &[1, 2]);
-    p.add_action("23", &[2, 3]);
-    p.add_action("0", &[0]);
-    p.add_action("1", &[1]);
-    p.add_action("2", &[2]);
-    p.add_action("3", &[3]);
+    p.add_action("01", &[0, 1]).unwrap();
+    p.add_action("03", &[0, 3]).unwrap();
+    p.add_action("12", &[1, 2]).unwrap();
+    p.add_action("23", &[2, 3]).unwrap();
+    p.add_action("0", &[0]).unwrap();
+    p.add_action("1", &[1]).unwrap();
+    p.add_action("2", &[2]).unwrap();
+    p.add_action("3", &[3]).unwrap();
 
     p
 }
@@ -40,8 +40,8 @@ fn count_multiple() {
 #[test]
 fn first_in_iter() {
     let mut p = Problem::new();
-    p.add_action(0, &[0, 1]);
-    p.add_action(1, &[2, 3]);
+    p.add_action(0, &[0, 1]).unwrap();
+    p.add_action(1, &[2, 3]).unwrap();
 
     let solver = Solver::new(p);
     let mut iter = solver.into_iter();
@@ -50,8 +50,27 @@ fn first_in_iter() {
     assert!(first_sol.is_some());
     let fs = first_sol.unwrap();
 
+    #[allow(clippy::useless_vec)]
     let real_solution = vec![0, 1];
     for i in real_solution.iter() {
         assert!(fs.iter().find(|x| {*x == i}).is_some());
     }
 }
+
+#[test]
+fn count_solutions() {
+    let solver = Solver::new(test_prob());
+    let n = solver.clone().into_iter().count();
+    assert_eq!(solver.count_solutions(None), n);
+    assert_eq!(solver.count_solutions(Some(3)), 3);
+    assert_eq!(solver.count_solutions(Some(0)), 0);
+    assert_eq!(solver.count_solutions(Some(n + 5)), n);
+}
+
+#[test]
+fn count_solutions_with_required() {
+    let mut solver = Solver::new(test_prob());
+    solver.require_action("01").unwrap();
+    assert_eq!(solver.count_solutions(None), solver.clone().into_iter().count());
+    assert_eq!(solver.count_solutions(None), 2);
+}
@@ -0,0 +1,106 @@
+extern crate exact_cover;
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use exact_cover::{Problem};
+use exact_cover::{Solver};
+use exact_cover::instances::sudoku;
+use common::{queens_problem, staffing, sorted};
+
+#[test]
+fn parallel_matches_sequential() {
+    for n in 1..9 {
+        let solver = Solver::new(queens_problem(n));
+        let expected = sorted(solver.clone().into_iter());
+        for threads in 1..4 {
+            assert_eq!(sorted(solver.par_solutions(threads)), expected);
+            assert_eq!(solver.par_count_solutions(threads), expected.len());
+        }
+    }
+}
+
+#[test]
+fn parallel_bounded() {
+    let solver = Solver::new(staffing(6, 3, 1, 3));
+    let expected = sorted(solver.clone().into_iter());
+    assert!(!expected.is_empty());
+    assert_eq!(sorted(solver.par_solutions(3)), expected);
+    assert_eq!(solver.par_count_solutions(0), expected.len());
+}
+
+#[test]
+fn parallel_with_required_actions() {
+    let mut solver = Solver::new(queens_problem(8));
+    solver.require_action((0, 0)).unwrap();
+    let expected = sorted(solver.clone().into_iter());
+    assert_eq!(expected.len(), 4);
+    assert_eq!(sorted(solver.par_solutions(2)), expected);
+
+    // Required actions which already form a solution.
+    let mut p = Problem::new();
+    p.add_action(0, &[0]).unwrap();
+    let mut solver = Solver::new(p);
+    solver.require_action(0).unwrap();
+    assert_eq!(solver.par_solutions(2).collect::<Vec<_>>(), vec![vec![0]]);
+}
+
+#[test]
+fn parallel_count_decompose() {
+    // Four boards of 6 queens side by side, with 4 solutions each.
+    let mut p = Problem::new();
+    for t in 0..4 {
+        for i in 0..11 {
+            p.add_secondary_constraint(&(t, 2, i));
+            p.add_secondary_constraint(&(t, 3, i));
+        }
+        for r in 0..6 {
+            for c in 0..6 {
+                p.add_action((t, r, c), &[(t, 0, r), (t, 1, c), (t, 2, r + c), (t, 3, 5 + r - c)]).unwrap();
+            }
+        }
+    }
+    let mut solver = Solver::new(p);
+    solver.set_decompose(true);
+    assert_eq!(solver.count_solutions(None), 256);
+    for threads in 1..4 {
+        assert_eq!(solver.par_count_solutions(threads), 256);
+    }
+}
+
+#[test]
+fn parallel_sudoku() {
+    let mut solver = Solver::new(sudoku::sudoku_problem(2).unwrap());
+    solver.require_action(sudoku::SudokuAction { cell: 1, row: 0, col: 0 }).unwrap();
+    assert_eq!(solver.par_count_solutions(4), solver.clone().into_iter().count());
+}
+
+#[test]
+fn drop_parallel_iterator_early() {
+    let solver = Solver::new(queens_problem(10));
+    let first: Vec<_> = solver.par_solutions(4).take(3).collect();
+    assert_eq!(first.len(), 3);
+}
+
+#[test]
+fn drop_parallel_iterator_on_hopeless_subtrees() {
+    // One action places every pigeon at once; any other first step
+    // leads to fitting 13 pigeons in 12 holes, which takes ages to
+    // rule out.
+    let mut p = Problem::new();
+    for h in 0..12 {
+        p.add_secondary_constraint(&(1, h));
+    }
+    p.add_action(None, &(0..13).map(|i| (0, i)).collect::<Vec<_>>()).unwrap();
+    for i in 0..13 {
+        for h in 0..12 {
+            p.add_action(Some((i, h)), &[(0, i), (1, h)]).unwrap();
+        }
+    }
+
+    let start = Instant::now();
+    let first: Vec<_> = Solver::new(p).par_solutions(2).take(1).collect();
+    assert_eq!(first, vec![vec![None]]);
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
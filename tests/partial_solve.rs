@@ -9,10 +9,10 @@ struct BasicAction(usize);
 #[test]
 fn partial_solve_fail() {
     let mut p = Problem::new();
-    p.add_action(BasicAction(0), &[0]);
-    p.add_action(BasicAction(1), &[0, 1, 2]);
-    p.add_action(BasicAction(2), &[2, 4]);
-    p.add_action(BasicAction(3), &[3, 4]);
+    p.add_action(BasicAction(0), &[0]).unwrap();
+    p.add_action(BasicAction(1), &[0, 1, 2]).unwrap();
+    p.add_action(BasicAction(2), &[2, 4]).unwrap();
+    p.add_action(BasicAction(3), &[3, 4]).unwrap();
 
     let mut solver = Solver::new(p);
     assert!(solver.require_action(BasicAction(1)).is_ok());
@@ -22,10 +22,10 @@ fn partial_solve_fail() {
 #[test]
 fn full_presolve() {
     let mut p = Problem::new();
-    p.add_action(0, &["a"]);
-    p.add_action(1, &["a", "b", "c"]);
-    p.add_action(2, &["c", "e"]);
-    p.add_action(3, &["d", "e"]);
+    p.add_action(0, &["a"]).unwrap();
+    p.add_action(1, &["a", "b", "c"]).unwrap();
+    p.add_action(2, &["c", "e"]).unwrap();
+    p.add_action(3, &["d", "e"]).unwrap();
 
     let mut solver = Solver::new(p);
     assert!(solver.require_action(1).is_ok());
@@ -43,10 +43,10 @@ fn full_presolve() {
 #[test]
 fn partial_presolve() {
     let mut p = Problem::new();
-    p.add_action(0, &["a"]);
-    p.add_action(1, &["a", "b", "c"]);
-    p.add_action(2, &["c", "e"]);
-    p.add_action(3, &["d", "e"]);
+    p.add_action(0, &["a"]).unwrap();
+    p.add_action(1, &["a", "b", "c"]).unwrap();
+    p.add_action(2, &["c", "e"]).unwrap();
+    p.add_action(3, &["d", "e"]).unwrap();
 
     let mut solver = Solver::new(p);
     assert!(solver.require_action(1).is_ok());
@@ -59,3 +59,17 @@ fn partial_presolve() {
         assert!(sol.iter().find(|y| *y == x).is_some())
     }
 }
+
+#[test]
+fn full_presolve_iter() {
+    let mut p = Problem::new();
+    p.add_action(0, &["a"]).unwrap();
+    p.add_action(1, &["b"]).unwrap();
+
+    let mut solver = Solver::new(p);
+    assert!(solver.require_action(0).is_ok());
+    assert!(solver.require_action(1).is_ok());
+
+    let sols: Vec<Vec<usize>> = solver.into_iter().collect();
+    assert_eq!(sols, vec![vec![0, 1]]);
+}
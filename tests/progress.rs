@@ -0,0 +1,59 @@
+extern crate exact_cover;
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use exact_cover::{Solver, Progress};
+use common::{queens_problem};
+
+fn recorder() -> (Arc<Mutex<Vec<Progress>>>, impl Fn(&Progress) + Send + Sync + 'static) {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let r = reports.clone();
+    (reports, move |p: &Progress| r.lock().unwrap().push(p.clone()))
+}
+
+#[test]
+fn iteration_progress() {
+    let (reports, observer) = recorder();
+    let mut solver = Solver::new(queens_problem(8));
+    solver.set_progress(1, observer);
+    let mut iter = solver.into_iter();
+    assert_eq!(iter.by_ref().count(), 92);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len() as u64, iter.stats().nodes);
+    for (i, p) in reports.iter().enumerate() {
+        assert_eq!(p.nodes, i as u64 + 1);
+        assert!(p.depth >= 1 && p.depth <= 8);
+        assert_eq!(p.branches.len(), p.depth);
+        assert_eq!(p.branches[0].1, 8);
+        assert!(p.branches.iter().all(|&(i, k)| i < k));
+        assert!(p.fraction >= 0.0 && p.fraction < 1.0);
+    }
+    assert!(reports.windows(2).all(|w| w[0].fraction <= w[1].fraction));
+    assert_eq!(reports[0].fraction, 0.0);
+    assert!(reports.last().unwrap().fraction > 0.8);
+}
+
+#[test]
+fn first_solution_progress() {
+    let (reports, observer) = recorder();
+    let mut solver = Solver::new(queens_problem(12));
+    solver.set_progress(10, observer);
+    let (result, stats) = solver.first_solution_with_stats();
+    assert!(result.found().is_some());
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len() as u64, stats.nodes / 10);
+    assert!(reports.iter().all(|p| p.nodes % 10 == 0));
+}
+
+#[test]
+fn iterator_progress() {
+    let (reports, observer) = recorder();
+    let mut iter = Solver::new(queens_problem(6)).into_iter();
+    iter.set_progress(5, observer);
+    assert_eq!(iter.by_ref().count(), 4);
+    assert_eq!(reports.lock().unwrap().len() as u64, iter.stats().nodes / 5);
+}
@@ -0,0 +1,66 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Solver};
+use exact_cover::instances::sudoku::{sudoku_problem, solution_as_matrix, SudokuAction};
+use common::{queens_problem, sorted};
+
+fn is_valid_sudoku(grid: &[Vec<usize>]) -> bool {
+    let n = grid.len();
+    let full = |cells: Vec<usize>| {
+        let mut cells = cells;
+        cells.sort();
+        cells == (1..n + 1).collect::<Vec<_>>()
+    };
+    (0..n).all(|i| full(grid[i].clone()) && full(grid.iter().map(|r| r[i]).collect())) &&
+        (0..n).all(|b| full((0..n).map(|k| grid[b / 3 * 3 + k / 3][b % 3 * 3 + k % 3]).collect()))
+}
+
+#[test]
+fn random_sudoku_grids() {
+    let solver = Solver::new(sudoku_problem(9).unwrap());
+    let grid = |seed| solution_as_matrix(9, &solver.random_solution(seed).unwrap());
+
+    for seed in 0..5 {
+        assert!(is_valid_sudoku(&grid(seed)));
+        assert_eq!(grid(seed), grid(seed));
+    }
+    assert!((1..5).any(|seed| grid(seed) != grid(0)));
+    assert_ne!(grid(0), solution_as_matrix(9, &solver.first_solution().unwrap()));
+}
+
+#[test]
+fn random_solution_with_required() {
+    let mut solver = Solver::new(sudoku_problem(9).unwrap());
+    let clue = SudokuAction { cell: 5, row: 4, col: 4 };
+    solver.require_action(clue).unwrap();
+    for seed in 0..5 {
+        let sol = solver.random_solution(seed).unwrap();
+        assert!(sol.contains(&clue));
+        assert_eq!(solution_as_matrix(9, &sol)[4][4], 5);
+    }
+}
+
+#[test]
+fn randomized_iterator() {
+    let expected = sorted(Solver::new(queens_problem(8)).into_iter());
+    let default_order: Vec<_> = Solver::new(queens_problem(8)).into_iter().collect();
+
+    let order = |seed| Solver::new(queens_problem(8)).randomized(seed).into_iter().collect::<Vec<_>>();
+    assert_eq!(order(1), order(1));
+    assert_eq!(sorted(order(1).into_iter()), expected);
+    assert_eq!(sorted(order(2).into_iter()), expected);
+    assert_ne!(order(1), default_order);
+    assert_ne!(order(1), order(2));
+
+    let solver = Solver::new(queens_problem(8)).randomized(3);
+    assert_eq!(solver.count_solutions(None), 92);
+    assert_eq!(sorted(solver.par_solutions(2)), expected);
+}
+
+#[test]
+fn random_solution_none() {
+    let solver = Solver::new(queens_problem(3));
+    assert!(solver.random_solution(0).is_none());
+}
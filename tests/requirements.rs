@@ -0,0 +1,127 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem, Solver};
+use exact_cover::instances::sudoku::{sudoku_solver, SudokuAction};
+
+fn problem() -> Problem<u32, char> {
+    // Bounds and colors, so undoing exercises every kind of cover.
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&'b', 1, 2);
+    p.add_secondary_constraint(&'s');
+    p.add_action(0, &['a', 'b']).unwrap();
+    p.add_colored_action(1, &['a'], &[('s', 1)]).unwrap();
+    p.add_colored_action(2, &['b', 'c'], &[('s', 1)]).unwrap();
+    p.add_colored_action(3, &['c'], &[('s', 2)]).unwrap();
+    p.add_action(4, &['b', 'c', 's']).unwrap();
+    p.add_action(5, &['d']).unwrap();
+    p.add_action(6, &['d', 'b']).unwrap();
+    p
+}
+
+fn solutions(solver: &Solver<u32, char>) -> Vec<Vec<u32>> {
+    let mut sols: Vec<Vec<u32>> = solver.clone().into_iter()
+        .map(|mut s| { s.sort(); s })
+        .collect();
+    sols.sort();
+    sols
+}
+
+/// Return the solutions of a fresh solver requiring `actions`.
+fn expected(actions: &[u32]) -> Vec<Vec<u32>> {
+    let mut solver = Solver::new(problem());
+    for &a in actions {
+        solver.require_action(a).unwrap();
+    }
+    solutions(&solver)
+}
+
+#[test]
+fn pop_requirement() {
+    let mut solver = Solver::new(problem());
+    assert_eq!(solver.pop_requirement(), None);
+
+    solver.require_action(1).unwrap();
+    solver.require_action(2).unwrap();
+    assert_eq!(solutions(&solver), expected(&[1, 2]));
+    assert_eq!(solver.pop_requirement(), Some(2));
+    assert_eq!(solutions(&solver), expected(&[1]));
+    assert_eq!(solver.pop_requirement(), Some(1));
+    assert_eq!(solutions(&solver), expected(&[]));
+    assert!(solver.required_actions().is_empty());
+}
+
+#[test]
+fn unrequire_action() {
+    let mut solver = Solver::new(problem());
+    for a in [1, 5, 2] {
+        solver.require_action(a).unwrap();
+    }
+    assert!(!solver.unrequire_action(0));
+    assert!(solver.unrequire_action(5));
+    assert_eq!(solver.required_actions(), &[1, 2]);
+    assert_eq!(solutions(&solver), expected(&[1, 2]));
+
+    // The freed constraint can be satisfied another way.
+    solver.require_action(6).unwrap();
+    assert_eq!(solutions(&solver), expected(&[1, 2, 6]));
+
+    solver.clear_requirements();
+    assert!(solver.required_actions().is_empty());
+    assert_eq!(solutions(&solver), expected(&[]));
+}
+
+#[test]
+fn scopes() {
+    let mut solver = Solver::new(problem());
+    assert_eq!(solver.pop_scope(), None);
+
+    solver.require_action(5).unwrap();
+    solver.push_scope();
+    solver.require_action(1).unwrap();
+    solver.push_scope();
+    solver.require_action(2).unwrap();
+    assert_eq!(solutions(&solver), expected(&[5, 1, 2]));
+
+    assert_eq!(solver.pop_scope(), Some(vec![2]));
+    assert_eq!(solutions(&solver), expected(&[5, 1]));
+    solver.require_action(4).unwrap_err();
+    assert_eq!(solver.pop_scope(), Some(vec![1]));
+    assert_eq!(solver.required_actions(), &[5]);
+    assert_eq!(solutions(&solver), expected(&[5]));
+
+    // Unrequiring an action from an outer scope keeps inner scopes
+    // in place.
+    solver.push_scope();
+    solver.require_action(4).unwrap();
+    solver.push_scope();
+    solver.require_action(1).unwrap_err();
+    solver.unrequire_action(5);
+    solver.require_action(3).unwrap_err();
+    assert_eq!(solver.pop_scope(), Some(vec![]));
+    assert_eq!(solver.pop_scope(), Some(vec![4]));
+    assert!(solver.required_actions().is_empty());
+    assert_eq!(solutions(&solver), expected(&[]));
+}
+
+#[test]
+fn sudoku_editor() {
+    let cells = [0, 0, 0, 4,
+                 0, 0, 0, 0,
+                 2, 0, 0, 0,
+                 0, 0, 3, 0];
+    let mut solver = sudoku_solver(&cells).unwrap();
+    let count = solver.count_solutions(None);
+    assert!(count > 1);
+
+    // Try out clues, and take them back.
+    solver.push_scope();
+    solver.require_action(SudokuAction::new(1, 0, 0)).unwrap();
+    solver.require_action(SudokuAction::new(3, 0, 1)).unwrap();
+    let narrowed = solver.count_solutions(None);
+    assert!(narrowed < count);
+    assert!(solver.unrequire_action(SudokuAction::new(1, 0, 0)));
+    assert!(solver.count_solutions(None) >= narrowed);
+    solver.pop_scope();
+    assert_eq!(solver.count_solutions(None), count);
+    assert_eq!(solver.required_actions().len(), 3);
+}
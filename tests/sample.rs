@@ -0,0 +1,84 @@
+extern crate exact_cover;
+
+use std::collections::HashMap;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver};
+
+/// Domino tilings of a `rows` x `cols` board.
+fn dominoes(rows: usize, cols: usize) -> Problem<(usize, usize, bool), (usize, usize)> {
+    let mut p = Problem::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            if c + 1 < cols {
+                p.add_action((r, c, true), &[(r, c), (r, c + 1)]).unwrap();
+            }
+            if r + 1 < rows {
+                p.add_action((r, c, false), &[(r, c), (r + 1, c)]).unwrap();
+            }
+        }
+    }
+    p
+}
+
+#[test]
+fn sampler_counts() {
+    assert_eq!(Solver::new(dominoes(2, 6)).sampler(0).num_solutions(), 13);
+    assert_eq!(Solver::new(dominoes(6, 6)).sampler(0).num_solutions(), 6728);
+    assert_eq!(Solver::new(dominoes(8, 8)).sampler(0).num_solutions(), 12988816);
+    assert_eq!(Solver::new(dominoes(3, 3)).sampler(0).num_solutions(), 0);
+}
+
+#[test]
+fn samples_are_uniform() {
+    let solver = Solver::new(dominoes(2, 6));
+    let mut sampler = solver.sampler(42);
+
+    let mut seen: HashMap<Vec<(usize, usize, bool)>, usize> = HashMap::new();
+    for _ in 0..13000 {
+        let mut s = sampler.sample().unwrap();
+        s.sort();
+        *seen.entry(s).or_insert(0) += 1;
+    }
+
+    let mut all: Vec<_> = solver.into_iter().map(|mut s| { s.sort(); s }).collect();
+    all.sort();
+    let mut sampled: Vec<_> = seen.keys().cloned().collect();
+    sampled.sort();
+    assert_eq!(sampled, all);
+
+    // Each of the 13 tilings is expected 1000 times, with a standard
+    // deviation of about 30.
+    for n in seen.values() {
+        assert!(*n > 850 && *n < 1150, "{:?}", seen);
+    }
+}
+
+#[test]
+fn sampler_is_seeded() {
+    let solver = Solver::new(dominoes(6, 6));
+    let a: Vec<_> = solver.sampler(7).take(20).collect();
+    let b: Vec<_> = solver.sampler(7).take(20).collect();
+    assert_eq!(a, b);
+
+    let mut sampler = solver.sampler(8);
+    assert_ne!(sampler.by_ref().take(20).collect::<Vec<_>>(), a);
+    sampler.reseed(7);
+    assert_eq!(sampler.take(20).collect::<Vec<_>>(), a);
+}
+
+#[test]
+fn sample_with_required() {
+    let mut solver = Solver::new(dominoes(4, 4));
+    solver.require_action((0, 0, true)).unwrap();
+    let mut sampler = solver.sampler(1);
+    assert_eq!(sampler.num_solutions() as usize, solver.count_solutions(None));
+    for _ in 0..20 {
+        let s = sampler.sample().unwrap();
+        assert_eq!(s[0], (0, 0, true));
+        assert_eq!(s.len(), 8);
+    }
+
+    let mut sampler = Solver::new(dominoes(3, 3)).sampler(1);
+    assert!(sampler.sample().is_none());
+}
@@ -0,0 +1,49 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Problem};
+use exact_cover::{Solver};
+use common::{queens_problem};
+
+#[test]
+fn queens_count() {
+    let counts = [1, 0, 0, 2, 10, 4, 40, 92];
+    for (i, count) in counts.iter().enumerate() {
+        let p = queens_problem(i + 1);
+        assert_eq!(p.num_secondary_constraints(), 4 * i + 2);
+        assert_eq!(Solver::new(p).into_iter().count(), *count);
+    }
+}
+
+#[test]
+fn secondary_need_not_be_covered() {
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&"s");
+    p.add_action(0, &["a", "s"]).unwrap();
+    p.add_action(1, &["b", "s"]).unwrap();
+    p.add_action(2, &["a"]).unwrap();
+    p.add_action(3, &["b"]).unwrap();
+
+    assert!(p.is_secondary(&"s"));
+    assert!(!p.is_secondary(&"a"));
+
+    // Both 0 and 1 cover "s", so they can't appear together.
+    let mut sols: Vec<Vec<usize>> = Solver::new(p).into_iter().map(|mut s| { s.sort(); s }).collect();
+    sols.sort();
+    assert_eq!(sols, vec![vec![0, 3], vec![1, 2], vec![2, 3]]);
+}
+
+#[test]
+fn require_secondary_conflict() {
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&"s");
+    p.add_action(0, &["a", "s"]).unwrap();
+    p.add_action(1, &["b", "s"]).unwrap();
+    p.add_action(2, &["b"]).unwrap();
+
+    let mut solver = Solver::new(p);
+    assert!(solver.require_action(0).is_ok());
+    assert!(solver.require_action(1).is_err());
+    assert_eq!(solver.first_solution().map(|s| s.len()), Some(2));
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "serde")]
+
+extern crate exact_cover;
+extern crate serde_json;
+
+use exact_cover::{Problem, Solver, Checkpoint};
+use exact_cover::instances::sudoku::{sudoku_solver, SudokuAction, SudokuConstraint, SudokuSolver, Location};
+
+fn problem() -> Problem<u32, String> {
+    let mut p = Problem::new();
+    p.add_secondary_constraint(&"s".to_string());
+    p.add_bounded_constraint(&"b".to_string(), 1, 2);
+    p.add_action(1, &["a".to_string(), "b".to_string()]).unwrap();
+    p.add_action(2, &["a".to_string(), "s".to_string()]).unwrap();
+    p.add_action(3, &["b".to_string()]).unwrap();
+    p.add_colored_action(4, &["a".to_string()], &[("x".to_string(), 1)]).unwrap();
+    p.set_action_cost(3, 7);
+    p
+}
+
+#[test]
+fn problem_round_trip() {
+    let p = problem();
+    let json = serde_json::to_string(&p).unwrap();
+    let q: Problem<u32, String> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(q.num_actions(), 4);
+    assert_eq!(q.num_constraints(), 4);
+    assert!(q.is_secondary(&"x".to_string()));
+    assert_eq!(q.constraint_bounds(&"b".to_string()), Some((1, 2)));
+    assert_eq!(q.action_cost(3), Some(7));
+    assert_eq!(serde_json::to_string(&q).unwrap(), json);
+
+    let mut a: Vec<_> = Solver::new(p).into_iter().collect();
+    let mut b: Vec<_> = Solver::new(q).into_iter().collect();
+    a.sort();
+    b.sort();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn problem_format() {
+    let mut p = Problem::new();
+    p.add_action(1, &[10, 11]).unwrap();
+    assert_eq!(serde_json::to_string(&p).unwrap(),
+               "{\"constraints\":[{\"constraint\":10,\"lo\":1,\"hi\":1},{\"constraint\":11,\"lo\":1,\"hi\":1}],\
+                \"actions\":[{\"action\":1,\"constraints\":[10,11]}]}");
+}
+
+#[test]
+fn invalid_problems() {
+    let dup = "{\"constraints\":[],\"actions\":[{\"action\":1,\"constraints\":[1]},{\"action\":1,\"constraints\":[2]}]}";
+    assert!(serde_json::from_str::<Problem<u32, u32>>(dup).is_err());
+    let bounds = "{\"constraints\":[{\"constraint\":1,\"lo\":2,\"hi\":1}],\"actions\":[]}";
+    assert!(serde_json::from_str::<Problem<u32, u32>>(bounds).is_err());
+
+    // Colored constraints must be secondary, and can't also be plain.
+    let primary = "{\"constraints\":[],\"actions\":[{\"action\":1,\"constraints\":[\"x\"],\"colored\":[[\"x\",0]]}]}";
+    assert!(serde_json::from_str::<Problem<u32, String>>(primary).is_err());
+    let both = "{\"constraints\":[{\"constraint\":\"x\",\"lo\":0,\"hi\":1}],\"actions\":[{\"action\":1,\"constraints\":[\"x\"],\"colored\":[[\"x\",0]]}]}";
+    assert!(serde_json::from_str::<Problem<u32, String>>(both).is_err());
+    let earlier = "{\"constraints\":[],\"actions\":[{\"action\":1,\"constraints\":[\"x\"]},{\"action\":2,\"constraints\":[],\"colored\":[[\"x\",0]]}]}";
+    assert!(serde_json::from_str::<Problem<u32, String>>(earlier).is_err());
+}
+
+#[test]
+fn solver_keeps_required_actions() {
+    let mut solver = Solver::new(problem());
+    solver.require_action(2).unwrap();
+    let json = serde_json::to_string(&solver).unwrap();
+    let restored: Solver<u32, String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.first_solution(), solver.first_solution());
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+}
+
+#[test]
+fn sudoku_types() {
+    let a = SudokuAction::new(5, 1, 2);
+    let json = serde_json::to_string(&a).unwrap();
+    assert_eq!(serde_json::from_str::<SudokuAction>(&json).unwrap(), a);
+
+    let c = SudokuConstraint::Uniqueness(3, Location::Box(0, 1));
+    let json = serde_json::to_string(&c).unwrap();
+    assert_eq!(serde_json::from_str::<SudokuConstraint>(&json).unwrap(), c);
+
+    let mut cells = [0; 16];
+    cells[0] = 1;
+    cells[5] = 3;
+    let solver = sudoku_solver(&cells).unwrap();
+    let restored: SudokuSolver = serde_json::from_str(&serde_json::to_string(&solver).unwrap()).unwrap();
+    assert_eq!(restored.count_solutions(None), solver.count_solutions(None));
+}
+
+#[test]
+fn checkpoint() {
+    let mut iter = Solver::new(problem()).into_iter();
+    iter.next();
+    let checkpoint = iter.checkpoint();
+    let json = serde_json::to_string(&checkpoint).unwrap();
+    assert_eq!(serde_json::from_str::<Checkpoint>(&json).unwrap(), checkpoint);
+}
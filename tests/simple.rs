@@ -0,0 +1,37 @@
+extern crate exact_cover;
+
+use exact_cover::{Solver, Error};
+use exact_cover::formats::simple;
+
+#[test]
+fn wikipedia_example() {
+    let input = "# The example from Wikipedia\n\
+                 A: 1 4 7\n\
+                 B: 1 4\n\
+                 \n\
+                 C: 4 5 7\n\
+                 D: 3 5 6\n\
+                 E: 2 3 6 7\n\
+                 F:2 7\n";
+    let (p, names) = simple::parse(input).unwrap();
+    assert_eq!(names, vec!["A", "B", "C", "D", "E", "F"]);
+    assert_eq!(p.num_constraints(), 7);
+
+    let mut sol = Solver::new(p).first_solution().unwrap();
+    sol.sort();
+    let sol: Vec<&str> = sol.iter().map(|&a| names[a].as_str()).collect();
+    assert_eq!(sol, vec!["B", "D", "F"]);
+}
+
+#[test]
+fn syntax_errors() {
+    let line = |input: &str| match simple::parse(input) {
+        Err(Error::Syntax { line, .. }) => line,
+        _ => panic!("expected a syntax error for {:?}", input)
+    };
+    assert_eq!(line("A: 1\nB 2\n"), 2);
+    assert_eq!(line("A: 1\nA: 2\n"), 2);
+    assert_eq!(line("# no constraints\nA:\n"), 2);
+    assert_eq!(line("A: 1 1\n"), 1);
+    assert_eq!(line("A B: 1\n"), 1);
+}
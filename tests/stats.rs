@@ -0,0 +1,74 @@
+extern crate exact_cover;
+
+mod common;
+
+use exact_cover::{Solver, SearchLimits, SearchResult};
+use exact_cover::chooser::FirstColumn;
+use common::{queens_problem};
+
+#[test]
+fn iterator_stats() {
+    let mut iter = Solver::new(queens_problem(8)).into_iter();
+    assert_eq!(iter.stats().nodes, 0);
+    assert_eq!(iter.by_ref().count(), 92);
+
+    let stats = iter.stats();
+    assert_eq!(stats.solutions, 92);
+    assert_eq!(stats.max_depth, 8);
+    assert!(stats.nodes > 92);
+    assert!(stats.updates > 0);
+    // The root branches once, on all 8 squares of a row or column.
+    assert_eq!(stats.branching[0].len(), 1);
+    assert_eq!(stats.branching[0].get(&8), Some(&1));
+    // Every node is a branch taken at some depth.
+    let branches: u64 = stats.branching.iter()
+        .flat_map(|h| h.iter().map(|(k, n)| *k as u64 * n))
+        .sum();
+    assert_eq!(branches, stats.nodes);
+}
+
+#[test]
+fn count_matches_iteration() {
+    let solver = Solver::new(queens_problem(7));
+    let (count, stats) = solver.count_solutions_with_stats(None);
+    let mut iter = solver.clone().into_iter();
+    assert_eq!(iter.by_ref().count(), count);
+    assert_eq!(stats, iter.stats());
+}
+
+#[test]
+fn first_solution_stats() {
+    let solver = Solver::new(queens_problem(8));
+    let (result, stats) = solver.first_solution_with_stats();
+    assert!(result.found().is_some());
+    assert_eq!(stats.solutions, 1);
+    assert_eq!(stats.max_depth, 8);
+    assert!(stats.nodes >= 8);
+
+    let (result, stats) = Solver::new(queens_problem(3)).first_solution_with_stats();
+    assert_eq!(result, SearchResult::NoSolution);
+    assert_eq!(stats.solutions, 0);
+}
+
+#[test]
+fn stats_survive_limits() {
+    let mut solver = Solver::new(queens_problem(6));
+    solver.set_limits(SearchLimits::new().max_nodes(10));
+    let mut iter = solver.into_iter();
+    assert!(iter.try_next().gave_up());
+    let nodes = iter.stats().nodes;
+    assert!(nodes > 0);
+
+    iter.set_limits(SearchLimits::new());
+    assert_eq!(iter.by_ref().count(), 4);
+    assert!(iter.stats().nodes > nodes);
+    assert_eq!(iter.stats().solutions, 4);
+}
+
+#[test]
+fn compare_choosers() {
+    let mrv = Solver::new(queens_problem(8)).count_solutions_with_stats(None);
+    let first = Solver::with_chooser(queens_problem(8), FirstColumn).count_solutions_with_stats(None);
+    assert_eq!(mrv.0, first.0);
+    assert_ne!(mrv.1.nodes, first.1.nodes);
+}
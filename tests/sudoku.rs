@@ -19,6 +19,7 @@ fn empty() {
     }
 }
 
+#[allow(clippy::needless_borrow, clippy::map_clone)]
 fn solution_as_array(sol: &Vec<SudokuAction>) -> Vec<usize> {
     solution_as_matrix(9, &sol).iter().flat_map(|x| x.iter() ).map(|x| *x).collect()
 }
@@ -0,0 +1,91 @@
+extern crate exact_cover;
+
+use exact_cover::{Problem, Solver, Verification};
+use exact_cover::instances::sudoku::{sudoku_problem, SudokuAction, SudokuConstraint, Location};
+
+fn wikipedia() -> Problem<&'static str, u32> {
+    let mut p = Problem::new();
+    p.add_action("A", &[1, 4, 7]).unwrap();
+    p.add_action("B", &[1, 4]).unwrap();
+    p.add_action("C", &[4, 5, 7]).unwrap();
+    p.add_action("D", &[3, 5, 6]).unwrap();
+    p.add_action("E", &[2, 3, 6, 7]).unwrap();
+    p.add_action("F", &[2, 7]).unwrap();
+    p
+}
+
+#[test]
+fn valid_solutions() {
+    let p = wikipedia();
+    let report = p.verify(&["D", "B", "F"]);
+    assert!(report.is_valid());
+    assert_eq!(report.to_string(), "valid solution");
+
+    for sol in Solver::new(p.clone()).into_iter() {
+        assert!(p.verify(&sol).is_valid());
+    }
+}
+
+#[test]
+fn diagnostics() {
+    let p = wikipedia();
+    let report = p.verify(&["A", "B", "G", "A"]);
+    assert!(!report.is_valid());
+    assert_eq!(report, Verification {
+        unknown_actions: vec!["G"],
+        duplicate_actions: vec!["A"],
+        uncovered: vec![(5, 0), (3, 0), (6, 0), (2, 0)],
+        overcovered: vec![(1, vec!["A", "B"]), (4, vec!["A", "B"])],
+        color_conflicts: vec![]
+    });
+    assert!(report.to_string().starts_with("unknown action \"G\"; duplicate action \"A\"; "));
+
+    assert_eq!(p.verify(&[]).uncovered.len(), 7);
+}
+
+#[test]
+fn bounds_and_colors() {
+    let mut p = Problem::new();
+    p.add_bounded_constraint(&"pair", 2, 2);
+    p.add_bounded_constraint(&"some", 0, 2);
+    p.add_secondary_constraint(&"s");
+    p.add_colored_action(0, &["pair"], &[("s", 1)]).unwrap();
+    p.add_colored_action(1, &["pair", "some"], &[("s", 1)]).unwrap();
+    p.add_colored_action(2, &["pair", "some"], &[("s", 2)]).unwrap();
+    p.add_action(3, &["pair", "some", "s"]).unwrap();
+
+    assert!(p.verify(&[0, 1]).is_valid());
+
+    let report = p.verify(&[1]);
+    assert_eq!(report.uncovered, vec![("pair", 1)]);
+
+    let report = p.verify(&[1, 2, 3]);
+    assert_eq!(report.overcovered, vec![("pair", vec![1, 2, 3]), ("some", vec![1, 2, 3])]);
+    assert_eq!(report.color_conflicts, vec![("s", vec![1, 2, 3])]);
+
+    assert_eq!(p.verify(&[0, 3]).color_conflicts, vec![("s", vec![0, 3])]);
+}
+
+#[test]
+fn sudoku_board() {
+    let board = [[1, 2, 3, 4],
+                 [3, 4, 1, 2],
+                 [2, 1, 4, 3],
+                 [4, 3, 2, 1]];
+    let actions = |board: &[[usize; 4]; 4]| -> Vec<SudokuAction> {
+        (0..4).flat_map(|r| (0..4).map(move |c| (r, c)))
+            .map(|(r, c)| SudokuAction::new(board[r][c], r, c))
+            .collect()
+    };
+    let p = sudoku_problem(4).unwrap();
+    assert!(p.verify(&actions(&board)).is_valid());
+
+    // Swapping two cells of a row breaks their columns and boxes.
+    let mut bad = board;
+    bad[0].swap(0, 2);
+    let report = p.verify(&actions(&bad));
+    assert!(report.unknown_actions.is_empty() && report.duplicate_actions.is_empty());
+    assert!(report.uncovered.contains(&(SudokuConstraint::Uniqueness(1, Location::Col(0)), 0)));
+    assert!(report.overcovered.contains(&(SudokuConstraint::Uniqueness(3, Location::Col(0)),
+                                          vec![SudokuAction::new(3, 0, 0), SudokuAction::new(3, 1, 0)])));
+}